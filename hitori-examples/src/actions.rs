@@ -0,0 +1,30 @@
+//! Annotating an all-pattern or an any-pattern with `#[hitori::on_match]` runs
+//! an action every time the subpattern matches. The action is a closure that
+//! is called with the matched index range and has mutable access to `self`.
+//!
+//! Actions are only available in [`impl_expr_mut`]. They are queued along
+//! with their ranges and run only after the whole pattern has matched, so
+//! actions of a branch that fails later are dropped and the implementor
+//! doesn't have to be [`Clone`]. Passing `transactional` argument runs each
+//! action immediately instead, rolling `self` back when a branch fails.
+//!
+//! ```
+#![doc = include_str!("actions/ident_list.rs")]
+//!
+//! let s = "foo,bar,baz;";
+//! let mut expr = IdentList::default();
+//! assert!(hitori::string::starts_with(&mut expr, s).is_some());
+//! assert_eq!(expr.idents, [0..3, 4..7, 8..11]);
+//!
+//! let mut expr = IdentList::default();
+//! assert!(hitori::string::starts_with(&mut expr, "foo,bar,baz").is_none());
+//! assert!(expr.idents.is_empty());
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! [`impl_expr_mut`]: hitori::impl_expr_mut
+//! [regex]: https://docs.rs/regex
+
+mod ident_list;
+
+pub use ident_list::{IdentList, IdentListCapture};
@@ -0,0 +1,29 @@
+use std::ops::Range;
+
+/// Comma-separated list of identifiers terminated with `;`,
+/// collecting the range of each identifier
+#[derive(Default)]
+pub struct IdentList {
+    pub idents: Vec<Range<usize>>,
+}
+
+#[hitori::impl_expr_mut]
+impl ExprMut<usize, char> for IdentList {
+    const PATTERN: _ = (
+        #[hitori::on_match(|range| self.idents.push(range))]
+        (
+            #[hitori::repeat(ge = 1)]
+            (char::is_alphabetic,),
+        ),
+        #[hitori::repeat(ge = 0)]
+        (
+            |ch| ch == ',',
+            #[hitori::on_match(|range| self.idents.push(range))]
+            (
+                #[hitori::repeat(ge = 1)]
+                (char::is_alphabetic,),
+            ),
+        ),
+        |ch| ch == ';',
+    );
+}
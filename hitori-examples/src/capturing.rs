@@ -34,6 +34,31 @@
 //! *equivalent to `(?P<last_item>🍄)?(?P<last_item>🫑)?(?P<last_item>🧀)?(?P<last_item>🥚)?`
 //! in [regex] syntax*
 //!
+//! ### First and longest captures
+//!
+//! Adding `keep = first` to the arguments of `#[hitori::capture]` makes
+//! it record only the first match of the group, while `keep = longest`
+//! replaces the capture only with a longer one. The default is `keep = last`.
+//!
+//! ```
+#![doc = include_str!("capturing/shopping_list_ends.rs")]
+//!
+//! let s = "🫑🧀🥚";
+//! let matched = hitori::string::starts_with(ShoppingListEnds, s).unwrap();
+//! assert_eq!(&s[matched.capture.first_item.unwrap()], "🫑");
+//! assert_eq!(&s[matched.capture.last_item.unwrap()], "🥚");
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! ```
+#![doc = include_str!("capturing/longest_word.rs")]
+//!
+//! let s = "one three seven";
+//! let matched = hitori::string::starts_with(LongestWord, s).unwrap();
+//! assert_eq!(&s[matched.capture.word.unwrap()], "three");
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
 //! ### Multiple destinations
 //!
 //! An expression can be captured into multiple destinations using
@@ -55,13 +80,274 @@
 //! *equivalent to `◾ (?P<width>(?P<height>\d))|▬ (?P<width>\d) (?P<height>\d)`
 //! in [regex] syntax*
 //!
+//! ### Repetition counts
+//!
+//! Annotating a group that contains a single repeated group with
+//! `#[hitori::count(dest)]` stores the number of matched repetitions
+//! into a [`usize`] field.
+//!
+//! ```
+#![doc = include_str!("capturing/heading.rs")]
+//!
+//! let s = "### Limitations";
+//! let matched = hitori::string::starts_with(Heading, s).unwrap();
+//! assert_eq!(matched.capture.level, 3);
+//! assert_eq!(&s[matched.capture.title.unwrap()], "Limitations");
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! ### Variants
+//!
+//! Annotating an any-pattern with `#[hitori::variants(A, B, C)]` records
+//! the branch that has matched into `variant` field. It is an enum with
+//! a variant for each branch, holding a struct with captures of that branch.
+//!
+//! ```
+#![doc = include_str!("capturing/shape.rs")]
+//!
+//! let s = "▬ 5 6";
+//! let matched = hitori::string::starts_with(Shape, s).unwrap();
+//! match matched.capture.variant.unwrap() {
+//!     ShapeCaptureVariant::Rect(rect) => {
+//!         assert_eq!(&s[rect.width.unwrap()], "5");
+//!         assert_eq!(&s[rect.height.unwrap()], "6");
+//!     }
+//!     ShapeCaptureVariant::Square(_) => unreachable!(),
+//! }
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! The field could be renamed with `dest` argument, which makes it possible
+//! to record branches of several any-patterns into the same capture struct.
+//!
+//! ```
+#![doc = include_str!("capturing/fan_setting.rs")]
+//!
+//! let matched = hitori::string::starts_with(FanSetting, "on hi").unwrap();
+//! assert!(matches!(matched.capture.power, Some(FanSettingCapturePower::On(_))));
+//! assert!(matches!(matched.capture.speed, Some(FanSettingCaptureSpeed::High(_))));
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! ### Nested capture structs
+//!
+//! Annotating a group with `#[hitori::capture_struct(dest)]` moves captures
+//! inside of it into a separate struct stored in `dest` field. The name of
+//! the nested struct is a concatenation of the outer struct name and `dest`
+//! in upper camel case.
+//!
+//! ```
+#![doc = include_str!("capturing/mailbox.rs")]
+//!
+//! let s = "user@example.com";
+//! let matched = hitori::string::starts_with(Mailbox, s).unwrap();
+//! assert_eq!(&s[matched.capture.user.unwrap()], "user");
+//! assert_eq!(&s[matched.capture.domain.name.unwrap()], "example.com");
+//! assert_eq!(&s[matched.capture.domain.extension.unwrap()], "com");
+//! ```
+//! *equivalent to `(?P<user>[\w\.+-]+)@(?P<name>[\w\.-]*(?:\.(?P<extension>[\w\.-]+))+)`
+//! in [regex] syntax*
+//!
+//! ### Name-based access
+//!
+//! Every generated struct implements [`Captures`] trait, which gives access
+//! to captured ranges by name, so that generic code could work with captures
+//! of any expression. Names of nested fields are joined with dots.
+//! [`MapIdx`] trait converts the index type of captured ranges.
+//!
+//! ```
+#![doc = include_str!("capturing/shape.rs")]
+//!
+//! use hitori::{Captures, MapIdx};
+//!
+//! fn describe<C: Captures<usize>>(s: &str, capture: &C) -> Vec<String> {
+//!     capture
+//!         .iter()
+//!         .map(|(name, range)| format!("{name}={}", &s[range]))
+//!         .collect()
+//! }
+//!
+//! let s = "▬ 5 6";
+//! let capture = hitori::string::starts_with(Shape, s).unwrap().capture;
+//! assert_eq!(describe(s, &capture), ["variant.Rect.height=6", "variant.Rect.width=5"]);
+//! assert_eq!(capture.get("variant.Rect.width"), Some(4..5));
+//! assert_eq!(capture.get("variant.Square.side"), None);
+//! assert!(ShapeCapture::<usize>::NAMES.contains(&"variant.Square.side"));
+//!
+//! let capture = capture.map_idx(|idx| idx as u32);
+//! assert_eq!(capture.get("variant.Rect.height"), Some(6..7));
+//! ```
+//!
+//! ### Required captures
+//!
+//! Adding `required` to the arguments of `#[hitori::capture]` makes the field
+//! a plain [`Range`](core::ops::Range) instead of an [`Option`]. The macro checks
+//! that such field is set on every match (e.g. it is not inside of an optional
+//! repetition or only a part of the any-pattern branches) and fails otherwise.
+//!
+//! ```
+#![doc = include_str!("capturing/release.rs")]
+//!
+//! let s = "1.64";
+//! let matched = hitori::string::starts_with(Release, s).unwrap();
+//! assert_eq!(&s[matched.capture.major], "1");
+//! assert_eq!(&s[matched.capture.minor], "64");
+//! assert_eq!(matched.capture.patch, None);
+//! ```
+//! *equivalent to `(?P<major>\d+)\.(?P<minor>\d+)(?:\.(?P<patch>\d+))?` in [regex] syntax*
+//!
+//! ```compile_fail
+//! struct YesOrNo;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for YesOrNo {
+//!     const PATTERN: _ = [
+//!         // error: `required` capture is not set on every match
+//!         #[hitori::capture(yes, required)]
+//!         (|ch| ch == 'y',),
+//!         |ch| ch == 'n',
+//!     ];
+//! }
+//! ```
+//!
+//! `required` fields of a nested capture struct are also checked against
+//! whether the `capture_struct` group itself is matched on every match.
+//!
+//! ```compile_fail
+//! struct MaybeInner;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for MaybeInner {
+//!     const PATTERN: _ = (
+//!         |ch| ch == 'a',
+//!         #[hitori::repeat(le = 1)]
+//!         (
+//!             #[hitori::capture_struct(inner)]
+//!             (
+//!                 // error: `required` capture is not set on every match
+//!                 #[hitori::capture(b, required)]
+//!                 (|ch| ch == 'b',),
+//!             ),
+//!         ),
+//!     );
+//! }
+//! ```
+//!
+//! ### Derives and attributes
+//!
+//! Generated structs derive [`Clone`], [`Eq`], [`PartialEq`] and [`Debug`].
+//! More derives could be added with `with_capture_derive` argument,
+//! and any other attributes with `with_capture_attr` argument.
+//!
+//! ```
+#![doc = include_str!("capturing/key_value.rs")]
+//!
+//! use std::collections::HashSet;
+//!
+//! let captures: HashSet<_> = ["a=1", "key=value", "b=2"]
+//!     .into_iter()
+//!     .map(|s| hitori::string::starts_with(KeyValue, s).unwrap().capture)
+//!     .collect();
+//! assert_eq!(captures.len(), 2);
+//! ```
+//!
+//! Captured ranges are [`Range`]s, which are not [`Copy`]. Passing
+//! `capture_pairs` argument stores them as `(start, end)` pairs instead,
+//! so that `Copy` could be derived as well.
+//!
+//! ```
+#![doc = include_str!("capturing/point.rs")]
+//!
+//! use hitori::Captures as _;
+//!
+//! let capture = hitori::string::starts_with(Point, "12,345").unwrap().capture;
+//! let copied = capture;
+//! assert_eq!(capture.x, (0, 2));
+//! assert_eq!(copied.y, Some((3, 6)));
+//! assert_eq!(capture.get("y"), Some(3..6));
+//! ```
+//!
+//! ### Capture sinks
+//!
+//! Passing an existing type as `with_capture_sink` argument (e.g.
+//! `with_capture_sink = "Spans"`) makes the macro store captures into it
+//! instead of generating a struct.
+//! The type has to implement [`CaptureSink`], which makes it possible to
+//! share a single capture type between several expressions.
+//!
+//! ```
+#![doc = include_str!("capturing/spans.rs")]
+//!
+//! fn main() {
+//!     let s = "width=42";
+//!     let matched = hitori::string::starts_with(Assignment, s).unwrap();
+//!     assert_eq!(matched.capture, Spans(vec![("name", 0..5), ("value", 6..8)]));
+//!
+//!     let s = "height<7";
+//!     let matched = hitori::string::starts_with(Comparison, s).unwrap();
+//!     assert_eq!(
+//!         matched.capture,
+//!         Spans(vec![("name", 0..6), ("operator", 6..7), ("value", 7..8)]),
+//!     );
+//! }
+//! ```
+//!
+//! ### Matching without capturing
+//!
+//! When only the fact of a match is needed, [`string::is_match`] and
+//! [`string::contains`] could be used instead of [`string::starts_with`]
+//! and [`string::find`]. They call [`ExprMut::is_match_mut`], which the macros
+//! implement for expressions with captures without any capture bookkeeping.
+//!
+//! ```
+#![doc = include_str!("capturing/fraction.rs")]
+//!
+//! assert!(hitori::string::is_match(Fraction, "4/5"));
+//! assert!(!hitori::string::is_match(Fraction, "1/0"));
+//!
+//! assert!(hitori::string::contains(Fraction, "half is 1/2"));
+//! assert!(!hitori::string::contains(Fraction, "1/0 is undefined"));
+//! ```
+//!
 //! [`ExprMut::Capture`]: hitori::ExprMut::Capture
+//! [`ExprMut::is_match_mut`]: hitori::ExprMut::is_match_mut
+//! [`string::is_match`]: hitori::string::is_match
+//! [`string::contains`]: hitori::string::contains
+//! [`string::starts_with`]: hitori::string::starts_with
+//! [`string::find`]: hitori::string::find
+//! [`CaptureSink`]: hitori::CaptureSink
+//! [`Captures`]: hitori::Captures
+//! [`MapIdx`]: hitori::MapIdx
+//! [`Range`]: core::ops::Range
 //! [regex]: https://docs.rs/regex
 
+mod fan_setting;
 mod fraction;
+mod heading;
+mod key_value;
+mod longest_word;
+mod mailbox;
+mod point;
 mod rectangle;
+mod release;
+mod shape;
 mod shopping_list;
+mod shopping_list_ends;
+mod spans;
 
+pub use fan_setting::{
+    FanSetting, FanSettingCapture, FanSettingCaptureHigh, FanSettingCaptureLow,
+    FanSettingCaptureOff, FanSettingCaptureOn, FanSettingCapturePower, FanSettingCaptureSpeed,
+};
 pub use fraction::{Fraction, FractionCapture};
+pub use heading::{Heading, HeadingCapture};
+pub use key_value::{KeyValue, KeyValueCapture};
+pub use longest_word::{LongestWord, LongestWordCapture};
+pub use mailbox::{Mailbox, MailboxCapture, MailboxCaptureDomain};
+pub use point::{Point, PointCapture};
 pub use rectangle::{Rectangle, RectangleCapture};
+pub use release::{Release, ReleaseCapture};
+pub use shape::{Shape, ShapeCapture, ShapeCaptureRect, ShapeCaptureSquare, ShapeCaptureVariant};
 pub use shopping_list::{ShoppingList, ShoppingListCapture};
+pub use shopping_list_ends::{ShoppingListEnds, ShoppingListEndsCapture};
+pub use spans::{Assignment, Comparison, Spans};
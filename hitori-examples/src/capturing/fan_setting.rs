@@ -0,0 +1,21 @@
+/// Power and speed of a fan, recording which of them has been chosen
+pub struct FanSetting;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for FanSetting {
+    const PATTERN: _ = (
+        // Record the matched branch into `FanSettingCapture.power`
+        #[hitori::variants(On, Off, dest = power)]
+        [
+            (|ch| ch == 'o', |ch| ch == 'n'),
+            (|ch| ch == 'o', |ch| ch == 'f', |ch| ch == 'f'),
+        ],
+        |ch| ch == ' ',
+        // Record the matched branch into `FanSettingCapture.speed`
+        #[hitori::variants(Low, High, dest = speed)]
+        [
+            (|ch| ch == 'l', |ch| ch == 'o'),
+            (|ch| ch == 'h', |ch| ch == 'i'),
+        ],
+    );
+}
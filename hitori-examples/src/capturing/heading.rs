@@ -0,0 +1,20 @@
+/// Markdown heading, counting its `#` characters
+pub struct Heading;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for Heading {
+    const PATTERN: _ = (
+        // Count into `HeadingCapture.level`
+        #[hitori::count(level)]
+        (
+            #[hitori::repeat(ge = 1, le = 6)]
+            (|ch| ch == '#',),
+        ),
+        |ch| ch == ' ',
+        #[hitori::capture(title)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch| ch != '\n',),
+        ),
+    );
+}
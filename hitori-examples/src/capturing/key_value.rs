@@ -0,0 +1,19 @@
+/// Key and value separated by `=`
+pub struct KeyValue;
+
+#[hitori::impl_expr(with_capture_derive = "Hash", with_capture_attr = "must_use")]
+impl Expr<usize, char> for KeyValue {
+    const PATTERN: _ = (
+        #[hitori::capture(key, required)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (char::is_alphanumeric,),
+        ),
+        |ch| ch == '=',
+        #[hitori::capture(value, required)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (char::is_alphanumeric,),
+        ),
+    );
+}
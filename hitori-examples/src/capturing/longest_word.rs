@@ -0,0 +1,19 @@
+/// Words separated by spaces, capturing the longest one
+pub struct LongestWord;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for LongestWord {
+    const PATTERN: _ = (
+        #[hitori::repeat(ge = 1)]
+        (
+            // Replace `LongestWordCapture.word` only with a longer one
+            #[hitori::capture(word, keep = longest)]
+            (
+                #[hitori::repeat(ge = 1)]
+                (char::is_alphabetic,),
+            ),
+            #[hitori::repeat(le = 1)]
+            (|ch| ch == ' ',),
+        ),
+    );
+}
@@ -0,0 +1,34 @@
+/// Email address with the domain captured into a nested struct
+pub struct Mailbox;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for Mailbox {
+    const PATTERN: _ = (
+        #[hitori::capture(user)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| {
+                ch == '.' || ch == '+' || ch == '-' || ch == '_' || ch.is_ascii_alphanumeric()
+            },),
+        ),
+        |ch| ch == '@',
+        // Capture into fields of `MailboxCapture.domain` of type `MailboxCaptureDomain`
+        #[hitori::capture_struct(domain)]
+        (
+            #[hitori::capture(name)]
+            (
+                #[hitori::repeat(ge = 0)]
+                (|ch: char| ch == '-' || ch == '_' || ch.is_ascii_alphanumeric(),),
+                #[hitori::repeat(ge = 1)]
+                (
+                    |ch| ch == '.',
+                    #[hitori::capture(extension)]
+                    (
+                        #[hitori::repeat(ge = 1)]
+                        (|ch: char| ch == '-' || ch == '_' || ch.is_ascii_alphanumeric(),),
+                    ),
+                ),
+            ),
+        ),
+    );
+}
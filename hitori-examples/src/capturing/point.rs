@@ -0,0 +1,19 @@
+/// Two numbers separated by `,`, where the second one is optional
+pub struct Point;
+
+#[hitori::impl_expr(capture_pairs, with_capture_derive = "Copy")]
+impl Expr<usize, char> for Point {
+    const PATTERN: _ = (
+        #[hitori::capture(x, required)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+        |ch| ch == ',',
+        #[hitori::capture(y)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+    );
+}
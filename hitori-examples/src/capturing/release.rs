@@ -0,0 +1,30 @@
+/// Release number with major and minor parts and an optional patch part
+pub struct Release;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for Release {
+    const PATTERN: _ = (
+        // `ReleaseCapture.major` is a `Range<usize>`, because it is always set
+        #[hitori::capture(major, required)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+        |ch| ch == '.',
+        #[hitori::capture(minor, required)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+        // `ReleaseCapture.patch` is an `Option<Range<usize>>`
+        #[hitori::repeat(le = 1)]
+        (
+            |ch| ch == '.',
+            #[hitori::capture(patch)]
+            (
+                #[hitori::repeat(ge = 1)]
+                (|ch: char| ch.is_ascii_digit(),),
+            ),
+        ),
+    );
+}
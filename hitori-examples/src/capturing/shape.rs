@@ -0,0 +1,30 @@
+/// Either a square with a side length, or a rectangle with width and height,
+/// recording which one has matched
+pub struct Shape;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for Shape {
+    const PATTERN: _ = (
+        // Record the matched branch into `ShapeCapture.variant`
+        #[hitori::variants(Square, Rect)]
+        [
+            (
+                |ch| ch == '◾',
+                char::is_whitespace,
+                // Capture into `ShapeCaptureSquare.side`
+                #[hitori::capture(side)]
+                (|ch: char| ch.is_ascii_digit(),),
+            ),
+            (
+                |ch| ch == '▬',
+                char::is_whitespace,
+                // Capture into `ShapeCaptureRect.width` and `ShapeCaptureRect.height`
+                #[hitori::capture(width)]
+                (|ch: char| ch.is_ascii_digit(),),
+                char::is_whitespace,
+                #[hitori::capture(height)]
+                (|ch: char| ch.is_ascii_digit(),),
+            ),
+        ],
+    );
+}
@@ -0,0 +1,23 @@
+/// Sequence of 🍄🫑🧀🥚, where each item may or may not be present,
+/// capturing the first and the last item in the list
+pub struct ShoppingListEnds;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for ShoppingListEnds {
+    const PATTERN: _ = (
+        #[hitori::repeat(ge = 0)]
+        (
+            // Capture into `ShoppingListEndsCapture.first_item` only once
+            #[hitori::capture(first_item, keep = first)]
+            (
+                #[hitori::capture(last_item)]
+                ([
+                    |ch| ch == '🍄',
+                    |ch| ch == '🫑',
+                    |ch| ch == '🧀',
+                    |ch| ch == '🥚',
+                ],),
+            ),
+        ),
+    );
+}
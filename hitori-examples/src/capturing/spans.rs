@@ -0,0 +1,62 @@
+use core::ops::Range;
+
+/// Named spans shared by [`Assignment`] and [`Comparison`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Spans(pub Vec<(&'static str, Range<usize>)>);
+
+impl hitori::CaptureSink<usize> for Spans {
+    type Snapshot = usize;
+
+    fn set(&mut self, name: &'static str, range: Range<usize>) {
+        self.0.push((name, range));
+    }
+
+    fn snapshot(&self) -> usize {
+        self.0.len()
+    }
+
+    fn restore(&mut self, snapshot: usize) {
+        self.0.truncate(snapshot);
+    }
+}
+
+/// Assignment of a number to a name, captured into [`Spans`]
+pub struct Assignment;
+
+#[hitori::impl_expr(with_capture_sink = "Spans")]
+impl Expr<usize, char> for Assignment {
+    const PATTERN: _ = (
+        #[hitori::capture(name)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (char::is_alphabetic,),
+        ),
+        |ch| ch == '=',
+        #[hitori::capture(value)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+    );
+}
+
+/// Comparison of a name with a number, captured into [`Spans`]
+pub struct Comparison;
+
+#[hitori::impl_expr(with_capture_sink = "Spans")]
+impl Expr<usize, char> for Comparison {
+    const PATTERN: _ = (
+        #[hitori::capture(name)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (char::is_alphabetic,),
+        ),
+        #[hitori::capture(operator)]
+        [|ch| ch == '<', |ch| ch == '>'],
+        #[hitori::capture(value)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+    );
+}
@@ -0,0 +1,46 @@
+//! Character tests are the leaves of a pattern. Each one is called with
+//! the next character of an input and returns [`bool`].
+//!
+//! ### Index-aware tests
+//!
+//! Annotating an all-pattern or an any-pattern with `#[hitori::with_index]`
+//! makes every test inside of it be called with two arguments: the index
+//! yielded along with the character (i.e. its **end**) and the character itself.
+//!
+//! ```
+#![doc = include_str!("character_tests/limited_word.rs")]
+//!
+//! let s = "hitori is fun";
+//! let matched = hitori::string::starts_with(LimitedWord(4), s).unwrap();
+//! assert_eq!(&s[matched.range], "hito");
+//!
+//! assert!(hitori::string::starts_with(LimitedWord(0), s).is_none());
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! ### Tests by reference
+//!
+//! Characters are passed to tests by value, so each test that sees a
+//! non-[`Copy`] character consumes it. Passing `by_ref` argument to the macro
+//! (or annotating a group with `#[hitori::by_ref]`) makes tests receive
+//! a reference to the character instead.
+//!
+//! ```
+#![doc = include_str!("character_tests/let_binding.rs")]
+//!
+//! let tokens = "let x = 42"
+//!     .split_whitespace()
+//!     .map(String::from)
+//!     .enumerate()
+//!     .map(|(i, token)| (i + 1, token));
+//! let matched = hitori::starts_with(LetBinding, 0, true, tokens).unwrap();
+//! assert_eq!(matched.capture.name, Some(1..2));
+//! ```
+//!
+//! [regex]: https://docs.rs/regex
+
+mod let_binding;
+mod limited_word;
+
+pub use let_binding::{LetBinding, LetBindingCapture};
+pub use limited_word::{LimitedWord, LimitedWordCapture};
@@ -0,0 +1,13 @@
+/// `let`, an identifier and `=` in a sequence of [`String`] tokens,
+/// capturing the identifier's index
+pub struct LetBinding;
+
+#[hitori::impl_expr(by_ref)]
+impl Expr<usize, String> for LetBinding {
+    const PATTERN: _ = (
+        |token: &String| token == "let",
+        #[hitori::capture(name)]
+        (|token: &String| token.chars().all(char::is_alphanumeric),),
+        |token: &String| token == "=",
+    );
+}
@@ -0,0 +1,11 @@
+/// Alphanumeric word that ends within the first `self.0` bytes of an input
+pub struct LimitedWord(pub usize);
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for LimitedWord {
+    const PATTERN: _ = #[hitori::with_index]
+    (
+        #[hitori::repeat(ge = 1)]
+        (|end, ch: char| end <= self.0 && ch.is_ascii_alphanumeric(),),
+    );
+}
@@ -0,0 +1,72 @@
+//! Implementing [`ExprWith`] instead of [`Expr`] makes the expression
+//! receive a context on every call. Character tests of such expression can
+//! read the context through `ctx` reference.
+//!
+//! An expression paired with its context using [`With`] implements [`Expr`].
+//!
+//! ```
+#![doc = include_str!("contexts/quoted.rs")]
+//!
+//! let s = "'hitori' is fun";
+//! let matched = hitori::string::starts_with(hitori::With::new(Quoted, '\''), s).unwrap();
+//! assert_eq!(&s[matched.capture.contents.unwrap()], "hitori");
+//!
+//! assert!(hitori::string::starts_with(hitori::With::new(Quoted, '"'), s).is_none());
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! [`With`] forwards [`ExprMut::min_len`], [`ExprMut::can_start_with`] and
+//! [`ExprMut::literal`] to the expression, so [`find`] uses them as well.
+//!
+//! ```
+#![doc = include_str!("contexts/quoted.rs")]
+//!
+//! use hitori::ExprMut;
+//!
+//! let expr = hitori::With::new(Quoted, '\'');
+//! assert_eq!(expr.min_len(), 2);
+//!
+//! let s = "say 'hi'";
+//! let found = hitori::string::find(expr, s).unwrap();
+//! assert_eq!(&s[found.range], "'hi'");
+//! ```
+//!
+//! A context can also be a reference, so that a single pattern type works
+//! with a configuration that is only known at runtime.
+//!
+//! ```
+#![doc = include_str!("contexts/keyword_assignment.rs")]
+//!
+//! let config = Config {
+//!     keywords: vec!["width", "height"],
+//! };
+//! let tokens = |s: &str| {
+//!     s.split_whitespace()
+//!         .map(String::from)
+//!         .enumerate()
+//!         .map(|(i, token)| (i + 1, token))
+//!         .collect::<Vec<_>>()
+//! };
+//! let expr = hitori::With::new(KeywordAssignment, &config);
+//!
+//! let matched = hitori::starts_with(&expr, 0, true, tokens("width = 42")).unwrap();
+//! assert_eq!(matched.capture.keyword, Some(0..1));
+//! assert_eq!(matched.capture.value, Some(2..3));
+//!
+//! assert!(hitori::starts_with(&expr, 0, true, tokens("depth = 42")).is_none());
+//! ```
+//!
+//! [`Expr`]: hitori::Expr
+//! [`ExprWith`]: hitori::ExprWith
+//! [`ExprMut::min_len`]: hitori::ExprMut::min_len
+//! [`ExprMut::can_start_with`]: hitori::ExprMut::can_start_with
+//! [`ExprMut::literal`]: hitori::ExprMut::literal
+//! [`find`]: hitori::string::find
+//! [`With`]: hitori::With
+//! [regex]: https://docs.rs/regex
+
+mod keyword_assignment;
+mod quoted;
+
+pub use keyword_assignment::{Config, KeywordAssignment, KeywordAssignmentCapture};
+pub use quoted::{Quoted, QuotedCapture};
@@ -0,0 +1,19 @@
+/// Keyword from the configuration passed as the context,
+/// followed by `=` and a value in a sequence of [`String`] tokens
+pub struct KeywordAssignment;
+
+/// Keywords allowed in a [`KeywordAssignment`]
+pub struct Config {
+    pub keywords: Vec<&'static str>,
+}
+
+#[hitori::impl_expr(by_ref)]
+impl<'a> ExprWith<usize, String, &'a Config> for KeywordAssignment {
+    const PATTERN: _ = (
+        #[hitori::capture(keyword)]
+        (|token: &String| ctx.keywords.contains(&token.as_str()),),
+        |token: &String| token == "=",
+        #[hitori::capture(value)]
+        (|_| true,),
+    );
+}
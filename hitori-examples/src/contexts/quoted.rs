@@ -0,0 +1,16 @@
+/// String literal enclosed in quotes passed as the context,
+/// capturing its contents
+pub struct Quoted;
+
+#[hitori::impl_expr]
+impl ExprWith<usize, char, char> for Quoted {
+    const PATTERN: _ = (
+        |ch| ch == *ctx,
+        #[hitori::capture(contents)]
+        (
+            #[hitori::repeat(ge = 0)]
+            (|ch| ch != *ctx,),
+        ),
+        |ch| ch == *ctx,
+    );
+}
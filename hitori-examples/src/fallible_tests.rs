@@ -0,0 +1,26 @@
+//! Tests of [`TryExprMut`] implementors return [`Result<bool, E>`](Result).
+//! The first error returned by a test stops matching and is passed
+//! through by [`try_starts_with`], [`try_find`] and their [`string`]
+//! counterparts.
+//!
+//! ```
+#![doc = include_str!("fallible_tests/budgeted_number.rs")]
+//!
+//! let s = "n = 123;";
+//! let found = hitori::string::try_find(BudgetedNumber { budget: 16 }, s).unwrap();
+//! assert_eq!(&s[found.unwrap().range], "123");
+//!
+//! let found = hitori::string::try_find(BudgetedNumber { budget: 6 }, s);
+//! assert_eq!(found.err(), Some(BudgetExceeded));
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! [`TryExprMut`]: hitori::TryExprMut
+//! [`try_starts_with`]: hitori::try_starts_with
+//! [`try_find`]: hitori::try_find
+//! [`string`]: hitori::string
+//! [regex]: https://docs.rs/regex
+
+mod budgeted_number;
+
+pub use budgeted_number::{BudgetExceeded, BudgetedNumber, BudgetedNumberCapture};
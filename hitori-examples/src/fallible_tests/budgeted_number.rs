@@ -0,0 +1,25 @@
+/// Digits that cost one unit of `self.budget` each
+pub struct BudgetedNumber {
+    pub budget: usize,
+}
+
+/// Error returned when [`BudgetedNumber`] runs out of budget
+#[derive(Debug, PartialEq, Eq)]
+pub struct BudgetExceeded;
+
+#[hitori::impl_expr_mut]
+impl TryExprMut<usize, char> for BudgetedNumber {
+    type Error = BudgetExceeded;
+
+    const PATTERN: _ = (
+        #[hitori::repeat(ge = 1)]
+        (|ch: char| self.spend().map(|()| ch.is_ascii_digit()),),
+    );
+}
+
+impl BudgetedNumber {
+    fn spend(&mut self) -> Result<(), BudgetExceeded> {
+        self.budget = self.budget.checked_sub(1).ok_or(BudgetExceeded)?;
+        Ok(())
+    }
+}
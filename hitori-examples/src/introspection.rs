@@ -0,0 +1,155 @@
+//! Both macros implement [`ExprAst`], which describes the pattern with
+//! a constant tree of [`Node`]s. It mirrors the `PATTERN` constant and
+//! could be inspected at runtime (e.g. by documentation generators,
+//! linters or pattern explainers).
+//!
+//! ```
+#![doc = include_str!("introspection/version.rs")]
+//!
+//! use hitori::ast::{Bound, ExprAst, Keep, Node};
+//!
+//! fn capture_names(node: &Node, names: &mut Vec<&'static str>) {
+//!     match node {
+//!         Node::All(nodes) | Node::Any(nodes) => {
+//!             for node in *nodes {
+//!                 capture_names(node, names);
+//!             }
+//!         }
+//!         Node::Capture { names: captured, node, .. } => {
+//!             names.extend_from_slice(captured);
+//!             capture_names(node, names);
+//!         }
+//!         Node::Repeat { node, .. }
+//!         | Node::CaptureStruct { node, .. }
+//!         | Node::Variants { node, .. }
+//!         | Node::Count { node, .. }
+//!         | Node::Position { node, .. }
+//!         | Node::WithIndex(node)
+//!         | Node::ByRef(node)
+//!         | Node::OnMatch { node, .. } => capture_names(node, names),
+//!         Node::Test(_) => (),
+//!     }
+//! }
+//!
+//! const AST: Node = <Version as ExprAst<usize, char>>::AST;
+//!
+//! let mut names = vec![];
+//! capture_names(&AST, &mut names);
+//! assert_eq!(names, ["major", "minor"]);
+//!
+//! let nodes = match AST {
+//!     Node::All(nodes) => nodes,
+//!     _ => unreachable!(),
+//! };
+//! assert_eq!(nodes[1], Node::Test("| ch | ch == '.'"));
+//! assert!(matches!(
+//!     nodes[0],
+//!     Node::Capture {
+//!         keep: Keep::Last,
+//!         is_required: false,
+//!         node: Node::All([Node::Repeat { lo: Bound::Lit(1), hi: None, .. }]),
+//!         ..
+//!     },
+//! ));
+//! ```
+//!
+//! # Pattern string
+//!
+//! [`ExprAst::pattern_string`] returns a regex-like rendering of the pattern,
+//! which is handy in error messages and code review. [Simple tests] are
+//! shown as characters and classes, while the other tests are shown as
+//! `<test>`. The rendering is also included
+//! in the documentation of the generated implementation.
+//!
+//! ```
+#![doc = include_str!("introspection/version.rs")]
+//!
+//! use hitori::ast::ExprAst;
+//!
+//! assert_eq!(
+//!     <Version as ExprAst<usize, char>>::pattern_string(),
+//!     r"([0-9]+)\.([0-9]+)",
+//! );
+//!
+//! struct Greeting;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for Greeting {
+//!     const PATTERN: _ = (
+//!         #[hitori::position(first)]
+//!         [
+//!             (|ch| ch == 'h', |ch| ch == 'i'),
+//!             (|ch| ch == 'y', |ch| ch == 'o'),
+//!         ],
+//!         #[hitori::repeat(ge = 0, le = 1)]
+//!         (|ch| ch == '!' || ch == '?',),
+//!         #[hitori::repeat(ge = 1)]
+//!         (char::is_whitespace,),
+//!     );
+//! }
+//!
+//! assert_eq!(
+//!     <Greeting as ExprAst<usize, char>>::pattern_string(),
+//!     "^(?:hi|yo)[!?]?<test>+",
+//! );
+//! ```
+//!
+//! # Generated documentation
+//!
+//! [`impl_expr`] and [`impl_expr_mut`] document what they generate, so that
+//! the rendered documentation of a crate explains its patterns. The implementation
+//! is documented with the pattern string, the number of characters a match
+//! consumes and `position` assertions, while every capture field is documented
+//! with the pattern it captures and the repetitions and any-pattern branches
+//! it is inside of. See the rendered documentation of [`Rectangle`]
+//! implementation and [`RectangleCapture::width`] field for an example.
+//!
+//! # DOT graph
+//!
+//! With **`dot`** feature enabled, [`Dot`] displays the pattern
+//! as a [DOT](https://graphviz.org/doc/info/lang.html) graph,
+//! which could be rendered into a diagram (e.g. with `dot -Tsvg`).
+//!
+#![cfg_attr(feature = "dot", doc = "```")]
+#![cfg_attr(not(feature = "dot"), doc = "```ignore")]
+#![doc = include_str!("introspection/version.rs")]
+//!
+//! use hitori::dot::Dot;
+//!
+//! assert_eq!(
+//!     Dot::of::<Version, usize, char>().to_string(),
+//!     r#"digraph {
+//!   ordering=out;
+//!   n0 [shape=box, label="sequence"];
+//!   n0 -> n1;
+//!   n1 [shape=box, label="capture major"];
+//!   n1 -> n2;
+//!   n2 [shape=box, label="repeat 1.."];
+//!   n2 -> n3;
+//!   n3 [shape=ellipse, label="| ch : char | ch.is_ascii_digit()"];
+//!   n0 -> n4;
+//!   n4 [shape=ellipse, label="| ch | ch == '.'"];
+//!   n0 -> n5;
+//!   n5 [shape=box, label="capture minor"];
+//!   n5 -> n6;
+//!   n6 [shape=box, label="repeat 1.."];
+//!   n6 -> n7;
+//!   n7 [shape=ellipse, label="| ch : char | ch.is_ascii_digit()"];
+//! }
+//! "#,
+//! );
+//! ```
+//!
+//! [`ExprAst`]: hitori::ast::ExprAst
+//! [`Dot`]: hitori::dot::Dot
+//! [`ExprAst::pattern_string`]: hitori::ast::ExprAst::pattern_string
+//! [`Node`]: hitori::ast::Node
+//! [`impl_expr`]: hitori::impl_expr
+//! [`impl_expr_mut`]: hitori::impl_expr_mut
+//! [Simple tests]: hitori#simple-tests
+//! [`Rectangle`]: crate::capturing::Rectangle
+//! [`RectangleCapture::width`]: crate::capturing::RectangleCapture::width
+
+mod version;
+
+pub use version::{Version, VersionCapture};
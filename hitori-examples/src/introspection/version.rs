@@ -0,0 +1,19 @@
+/// Version number with major and minor parts (e.g. `1.64`)
+pub struct Version;
+
+#[hitori::impl_expr]
+impl Expr<usize, char> for Version {
+    const PATTERN: _ = (
+        #[hitori::capture(major)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+        |ch| ch == '.',
+        #[hitori::capture(minor)]
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch: char| ch.is_ascii_digit(),),
+        ),
+    );
+}
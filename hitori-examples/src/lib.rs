@@ -4,10 +4,16 @@
 //! [`impl_expr`]: hitori::impl_expr
 //! [`impl_expr_mut`]: hitori::impl_expr_mut
 
+pub mod actions;
 pub mod all_patterns;
 pub mod any_patterns;
 pub mod capturing;
+pub mod character_tests;
+pub mod contexts;
+pub mod fallible_tests;
 pub mod generics;
+pub mod introspection;
+pub mod mutable_state;
 pub mod positions;
 pub mod putting_everything_together;
 pub mod repetitions;
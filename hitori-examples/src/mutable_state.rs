@@ -0,0 +1,41 @@
+//! Tests of [`ExprMut`] implementors can change the state of `self`.
+//!
+//! By default, state is not restored when a branch fails, so the next branch
+//! observes changes made by the failed one. Passing `transactional` argument
+//! to [`impl_expr_mut`] saves a clone of `self` before every branch and restores
+//! it if the branch fails.
+//!
+//! ```
+#![doc = include_str!("mutable_state/countdown.rs")]
+//!
+//! let mut expr = Countdown { next: 3 };
+//! let matched = hitori::string::starts_with(&mut expr, "321?").unwrap();
+//! assert_eq!(matched.range, 0..4);
+//! assert_eq!(expr.next, 0);
+//! ```
+//! *has no equivalent in [regex] syntax*
+//!
+//! ### Checkpoints
+//!
+//! Cloning the whole implementor could be expensive or impossible. Passing
+//! `transactional = "checkpoint"` uses [`Checkpoint`] implementation
+//! to save and restore only a part of the state.
+//!
+//! ```
+#![doc = include_str!("mutable_state/countdown_checkpoint.rs")]
+//!
+//! let mut expr = CountdownCheckpoint { next: 3, log: vec![] };
+//! assert!(hitori::string::starts_with(&mut expr, "321?").is_some());
+//! assert_eq!(expr.log, [3, 2, 1, 3, 2, 1]);
+//! ```
+//!
+//! [`ExprMut`]: hitori::ExprMut
+//! [`impl_expr_mut`]: hitori::impl_expr_mut
+//! [`Checkpoint`]: hitori::Checkpoint
+//! [regex]: https://docs.rs/regex
+
+mod countdown;
+mod countdown_checkpoint;
+
+pub use countdown::{Countdown, CountdownCapture};
+pub use countdown_checkpoint::{CountdownCheckpoint, CountdownCheckpointCapture};
@@ -0,0 +1,32 @@
+/// Digits counting down from `self.next` followed by either `!` or `?`
+#[derive(Clone)]
+pub struct Countdown {
+    pub next: u32,
+}
+
+#[hitori::impl_expr_mut(transactional)]
+impl ExprMut<usize, char> for Countdown {
+    const PATTERN: _ = [
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch| self.countdown(ch),),
+            |ch| ch == '!',
+        ),
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch| self.countdown(ch),),
+            |ch| ch == '?',
+        ),
+    ];
+}
+
+impl Countdown {
+    fn countdown(&mut self, ch: char) -> bool {
+        if ch.to_digit(10) == Some(self.next) {
+            self.next -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
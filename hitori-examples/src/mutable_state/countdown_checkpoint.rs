@@ -0,0 +1,47 @@
+/// Digits counting down from `self.next`, followed by either `!` or `?`,
+/// that also keeps a log of every digit it has seen
+pub struct CountdownCheckpoint {
+    pub next: u32,
+    pub log: Vec<u32>,
+}
+
+impl hitori::Checkpoint for CountdownCheckpoint {
+    type Saved = u32;
+
+    fn checkpoint(&self) -> u32 {
+        self.next
+    }
+
+    fn rollback(&mut self, saved: u32) {
+        self.next = saved;
+    }
+}
+
+#[hitori::impl_expr_mut(transactional = "checkpoint")]
+impl ExprMut<usize, char> for CountdownCheckpoint {
+    const PATTERN: _ = [
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch| self.countdown(ch),),
+            |ch| ch == '!',
+        ),
+        (
+            #[hitori::repeat(ge = 1)]
+            (|ch| self.countdown(ch),),
+            |ch| ch == '?',
+        ),
+    ];
+}
+
+impl CountdownCheckpoint {
+    fn countdown(&mut self, ch: char) -> bool {
+        let digit = ch.to_digit(10);
+        self.log.extend(digit);
+        if digit == Some(self.next) {
+            self.next -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -20,6 +20,40 @@
 //! `^(?P<last_car>(?P<first_car>🚃))$|^(?P<first_car1>🚃)🚃{3}(?P<last_car1>🚃)$`
 //!  in [regex] syntax*
 //!
+//! Searching functions pass the same `is_first` argument at every start
+//! position they try, whether or not they look for [`ExprMut::literal`]
+//! first, so [`string::find`] that does and [`find`] that doesn't give
+//! the same results.
+//!
+//! ```
+//! struct FirstAThenB;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for FirstAThenB {
+//!     const PATTERN: _ = (
+//!         #[hitori::position(first)]
+//!         (|ch| ch == 'a',),
+//!         |ch| ch == 'b',
+//!     );
+//! }
+//!
+//! use hitori::{string::CharEnds, ExprMut};
+//!
+//! let s = "xab";
+//! assert!(FirstAThenB.literal().is_some());
+//!
+//! let generic_found = hitori::find(FirstAThenB, 0, true, CharEnds::from(s));
+//! let found = hitori::string::find(FirstAThenB, s);
+//! assert_eq!(found.map(|found| found.range), generic_found.map(|found| found.range));
+//! assert_eq!(
+//!     hitori::string::contains(FirstAThenB, s),
+//!     hitori::contains(FirstAThenB, 0, true, CharEnds::from(s)),
+//! );
+//! ```
+//!
+//! [`ExprMut::literal`]: hitori::ExprMut::literal
+//! [`string::find`]: hitori::string::find
+//! [`find`]: hitori::find
 //! [regex]: https://docs.rs/regex
 
 mod train_cars;
@@ -48,16 +48,220 @@
 //! let expr = WouldYouKindly::default();
 //! let matched = hitori::string::starts_with(expr, s).unwrap();
 //! assert_eq!(&s[matched.capture.request.unwrap()], "lower that weapon for a moment");
+//!
+//! let s = "Would you... Would you kindly open the door?";
+//! let found = hitori::string::find(WouldYouKindly::default(), s).unwrap();
+//! assert_eq!(&s[found.capture.request.unwrap()], "open the door");
 //!```
 //! *equivalent to `Would you kindly (?P<request>[^?!]+)[?!]` in [regex] syntax*
 //!
+//! Note that `phrase_chars` are advanced by the test. To search for the
+//! expression with [`find`], the state is brought back by `reset` method,
+//! which is called before each attempt to match.
+//!
+//! ### Match length bounds
+//!
+//! The macros implement [`ExprLen`] using literal repetition bounds.
+//! Expression bounds are treated as unknown, so they make `MAX_LEN` unbounded.
+//! [`find`] uses the minimum length to stop searching early.
+//!
+//! ```
+#![doc = include_str!("repetitions/binary_u32.rs")]
+//!
+//! use hitori::ExprLen;
+//!
+//! assert_eq!(<BinaryU32 as ExprLen<usize, char>>::MIN_LEN, 3);
+//! assert_eq!(<BinaryU32 as ExprLen<usize, char>>::MAX_LEN, Some(34));
+//!
+//! let is_too_long = |s: &str| match <BinaryU32 as ExprLen<usize, char>>::MAX_LEN {
+//!     Some(max_len) => s.chars().count() > max_len,
+//!     None => false,
+//! };
+//! assert!(is_too_long(&format!("0b{}", "1".repeat(33))));
+//! assert!(!is_too_long("0b101"));
+//! ```
+//!
+//! ### Start characters
+//!
+//! If every match starts with a character passing one of [simple tests],
+//! the macros also implement [`ExprMut::can_start_with`]. Leading groups that
+//! could match nothing are looked through. [`find`] skips the start positions
+//! rejected by it without trying to match.
+//!
+//! ```
+#![doc = include_str!("repetitions/binary_u32.rs")]
+//!
+//! use hitori::ExprMut;
+//!
+//! assert!(BinaryU32.can_start_with(&'0'));
+//! assert!(!BinaryU32.can_start_with(&'1'));
+//!
+//! let s = "1111 0b1111";
+//! assert_eq!(hitori::string::find(BinaryU32, s).unwrap().range, 5..11);
+//! ```
+//!
+//! ### Required literals
+//!
+//! If every match contains characters in a row that are tested with
+//! single-literal comparisons (e.g. `|ch| ch == '@'`), the macros also
+//! implement [`ExprMut::literal`]. [`string::find`] and [`string::find_iter`]
+//! search for it with [`str::find`] and only try the start positions close
+//! enough to it.
+//!
+//! ```
+#![doc = include_str!("repetitions/binary_u32.rs")]
+//!
+//! use hitori::{ExprMut, Literal};
+//!
+//! assert_eq!(
+//!     BinaryU32.literal(),
+//!     Some(Literal {
+//!         text: "0b",
+//!         max_offset: Some(0),
+//!     }),
+//! );
+//!
+//! let s = "10 0b 0b12 0b01";
+//! let found: Vec<_> = hitori::string::find_iter(BinaryU32, s)
+//!     .map(|found| &s[found.range])
+//!     .collect();
+//! assert_eq!(found, ["0b1", "0b01"]);
+//! ```
+//!
+//! ### Rejected patterns
+//!
+//! Repetitions do not give characters back, so the macros reject an element
+//! that could only start with characters consumed by the preceding unbounded
+//! repetition, as well as an any-pattern branch that could only start with
+//! characters matched by an earlier single-character branch. It is done for
+//! tests that are identical closures or [simple tests].
+//!
+//! ```compile_fail
+//! struct RoundNumber;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for RoundNumber {
+//!     const PATTERN: _ = (
+//!         #[hitori::capture(digits)]
+//!         (
+//!             #[hitori::repeat(ge = 0)]
+//!             (|ch: char| ch.is_ascii_digit(),),
+//!         ),
+//!         // error: this never matches, because the preceding repetition
+//!         // consumes every character it could start with
+//!         |ch| ch == '0',
+//!     );
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! struct Letter;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for Letter {
+//!     const PATTERN: _ = [
+//!         #[hitori::capture(lowercase)]
+//!         (|ch: char| ('a'..='z').contains(&ch),),
+//!         // error: this branch is never chosen,
+//!         // because an earlier branch always matches first
+//!         #[hitori::capture(x)]
+//!         (|ch| ch == 'x',),
+//!     ];
+//! }
+//! ```
+//!
+//! Elements that could start with other characters, and branches
+//! in the order from the narrowest to the widest, are accepted.
+//!
+//! ```
+//! struct DigitsThenDot;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for DigitsThenDot {
+//!     const PATTERN: _ = (
+//!         #[hitori::capture(digits)]
+//!         (
+//!             #[hitori::repeat(ge = 0)]
+//!             (|ch: char| ch.is_ascii_digit(),),
+//!         ),
+//!         |ch| ch == '.',
+//!     );
+//! }
+//!
+//! struct Letter;
+//!
+//! #[hitori::impl_expr]
+//! impl Expr<usize, char> for Letter {
+//!     const PATTERN: _ = [
+//!         #[hitori::capture(x)]
+//!         (|ch| ch == 'x',),
+//!         #[hitori::capture(lowercase)]
+//!         (|ch: char| ('a'..='z').contains(&ch),),
+//!     ];
+//! }
+//!
+//! assert!(hitori::string::is_match(DigitsThenDot, "12."));
+//! let matched = hitori::string::starts_with(Letter, "x").unwrap();
+//! assert_eq!(matched.capture.x, Some(0..1));
+//! ```
+//!
+//! ### Automata
+//!
+//! If every test is one of the simple tests and there are no attributes
+//! other than `repeat` with literal bounds, passing `automaton` argument
+//! to [`impl_expr`] compiles the pattern into a deterministic automaton,
+//! which matches in linear time. Such patterns are matched like in [regex]:
+//! repetitions give characters back when the rest of the pattern needs them,
+//! and the macros don't reject them.
+//!
+//! ```
+#![doc = include_str!("repetitions/round_number.rs")]
+//!
+//! let s = "1200 apples";
+//! assert_eq!(hitori::string::starts_with(RoundNumber, s).unwrap().range, 0..4);
+//!
+//! let s = "12 apples, 30 pears";
+//! assert_eq!(hitori::string::find(RoundNumber, s).unwrap().range, 11..13);
+//! ```
+//! *equivalent to `[0-9]*0` in [regex] syntax*
+//!
+//! Patterns that cannot be compiled into an automaton are rejected
+//! instead of being matched step-by-step.
+//!
+//! ```compile_fail
+//! struct RoundNumber;
+//!
+//! #[hitori::impl_expr(automaton)]
+//! impl Expr<usize, char> for RoundNumber {
+//!     const PATTERN: _ = (
+//!         // error: `automaton` cannot be used with attributes other than `repeat`
+//!         #[hitori::capture(digits)]
+//!         (
+//!             #[hitori::repeat(ge = 0)]
+//!             (|ch: char| ch.is_ascii_digit(),),
+//!         ),
+//!         |ch| ch == '0',
+//!     );
+//! }
+//! ```
+//!
 //! [regex]: https://docs.rs/regex
 //! [`ExprMut`]: hitori::ExprMut
+//! [`impl_expr`]: hitori::impl_expr
+//! [`ExprLen`]: hitori::ExprLen
+//! [`ExprMut::can_start_with`]: hitori::ExprMut::can_start_with
+//! [`ExprMut::literal`]: hitori::ExprMut::literal
+//! [`string::find`]: hitori::string::find
+//! [`string::find_iter`]: hitori::string::find_iter
+//! [`find`]: hitori::string::find
+//! [simple tests]: hitori#simple-tests
 
 mod binary_u32;
 mod identifier;
+mod round_number;
 mod would_you_kindly;
 
 pub use binary_u32::{BinaryU32, BinaryU32Capture};
 pub use identifier::{Identifier, IdentifierCapture};
+pub use round_number::{RoundNumber, RoundNumberCapture};
 pub use would_you_kindly::{WouldYouKindly, WouldYouKindlyCapture};
@@ -0,0 +1,11 @@
+/// Decimal number ending with zero (e.g. `1200`)
+pub struct RoundNumber;
+
+#[hitori::impl_expr(automaton)]
+impl Expr<usize, char> for RoundNumber {
+    const PATTERN: _ = (
+        #[hitori::repeat(ge = 0)]
+        (|ch: char| ch.is_ascii_digit(),),
+        |ch| ch == '0',
+    );
+}
@@ -25,4 +25,8 @@ impl ExprMut<usize, char> for WouldYouKindly {
         ),
         |ch| ch == '?' || ch == '!',
     );
+
+    fn reset(&mut self) {
+        self.phrase_chars = PHRASE.chars();
+    }
 }
@@ -3,103 +3,560 @@ mod starts_with_block;
 use crate::{parse, utils::hitori_ident};
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote, ToTokens};
-use std::collections::BTreeSet;
-use syn::{punctuated::Punctuated, GenericParam, Token, Type, Visibility, WhereClause};
+use starts_with_block::{CaptureField, CaptureFields, FieldDocs};
+use syn::{punctuated::Punctuated, GenericParam, Meta, Path, Token, Type, Visibility, WhereClause};
 
 fn impl_decl(
     hitori_ident: &Ident,
     self_ty: &Type,
     trait_ident: &Ident,
-    idx_ty: &Type,
-    ch_ty: &Type,
+    trait_args: &TokenStream,
     generic_params: &Punctuated<GenericParam, Token![,]>,
     where_clause: Option<&WhereClause>,
 ) -> TokenStream {
     quote! {
-        impl<#generic_params> #hitori_ident::#trait_ident<#idx_ty, #ch_ty> for #self_ty
+        impl<#generic_params> #hitori_ident::#trait_ident<#trait_args> for #self_ty
         #where_clause
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn starts_with_sig(
     hitori_ident: &Ident,
     is_mut: bool,
     starts_with_ident: &Ident,
+    capture_trait: &TokenStream,
     iter_ident: &Ident,
     idx_ty: &Type,
     ch_ty: &Type,
+    ctx_ty: Option<&Type>,
+    is_try: bool,
     inline: bool,
 ) -> TokenStream {
     let inline = inline.then(|| quote! { #[inline] });
     let mut_ = is_mut.then(<Token![mut]>::default);
+    let ctx_param = ctx_ty.map(|ty| quote! { ctx: #ty, });
+    let mut output = quote! {
+        ::core::option::Option<#hitori_ident::Match<
+            #idx_ty,
+            <Self as #hitori_ident::#capture_trait>::Capture,
+            #iter_ident::IntoIter,
+        >>
+    };
+    if is_try {
+        output = quote! {
+            ::core::result::Result<
+                #output,
+                <Self as #hitori_ident::#capture_trait>::Error,
+            >
+        };
+    }
     quote! {
         #inline
         fn #starts_with_ident<#iter_ident>(
             &#mut_ self,
+            #ctx_param
             mut start: #idx_ty,
             is_first: bool,
             iter: #iter_ident,
-        ) -> ::core::option::Option<#hitori_ident::Match<
-            #idx_ty,
-            <Self as #hitori_ident::ExprMut<#idx_ty, #ch_ty>>::Capture,
-            #iter_ident::IntoIter,
-        >>
+        ) -> #output
         where
             #iter_ident: ::core::iter::IntoIterator<Item = (#idx_ty, #ch_ty)>,
             #iter_ident::IntoIter: ::core::clone::Clone,
     }
 }
 
-fn type_capture(capture_ident: &Ident, idx_ty: &Type) -> TokenStream {
-    quote! { type Capture = #capture_ident<#idx_ty>; }
+fn is_match_sig(
+    is_mut: bool,
+    iter_ident: &Ident,
+    idx_ty: &Type,
+    ch_ty: &Type,
+    inline: bool,
+) -> TokenStream {
+    let inline = inline.then(|| quote! { #[inline] });
+    let (mut_, is_match_ident) = if is_mut {
+        (
+            Some(<Token![mut]>::default()),
+            format_ident!("is_match_mut"),
+        )
+    } else {
+        (None, format_ident!("is_match"))
+    };
+    quote! {
+        #inline
+        fn #is_match_ident<#iter_ident>(
+            &#mut_ self,
+            start: #idx_ty,
+            is_first: bool,
+            iter: #iter_ident,
+        ) -> bool
+        where
+            #iter_ident: ::core::iter::IntoIterator<Item = (#idx_ty, #ch_ty)>,
+            #iter_ident::IntoIter: ::core::clone::Clone,
+    }
+}
+
+fn capture_ty(capture_ident: &Ident, capture_sink: Option<&Type>, idx_ty: &Type) -> TokenStream {
+    capture_sink.map_or_else(
+        || quote! { #capture_ident<#idx_ty> },
+        |ty| ty.to_token_stream(),
+    )
+}
+
+fn type_capture(capture_ty: &TokenStream) -> TokenStream {
+    quote! { type Capture = #capture_ty; }
+}
+
+fn idx_bound(idx_ident: &Ident, default_idx_ty: Option<&Type>) -> TokenStream {
+    default_idx_ty.map_or_else(
+        || idx_ident.to_token_stream(),
+        |ty| quote! { #idx_ident = #ty },
+    )
+}
+
+fn capture_attrs(
+    derives: &Punctuated<Path, Token![,]>,
+    attrs: &Punctuated<Meta, Token![,]>,
+) -> TokenStream {
+    let derives = derives.iter();
+    let attrs = attrs.iter();
+    quote! {
+        #[derive(
+            ::core::clone::Clone,
+            ::core::cmp::Eq,
+            ::core::cmp::PartialEq,
+            ::core::fmt::Debug,
+            #(#derives,)*
+        )]
+        #(#[#attrs])*
+    }
+}
+
+fn capture_names(
+    fields: &CaptureFields,
+    place: &TokenStream,
+    prefix: &str,
+    output: &mut Vec<(String, TokenStream)>,
+) {
+    for (ident, field) in &fields.fields {
+        let name = format!("{prefix}{ident}");
+        match field {
+            CaptureField::Range {
+                is_required: true,
+                is_pair: false,
+            } => output.push((
+                name,
+                quote! {
+                    ::core::option::Option::Some(::core::clone::Clone::clone(&#place.#ident))
+                },
+            )),
+            CaptureField::Range {
+                is_required: false,
+                is_pair: false,
+            } => output.push((name, quote! { ::core::clone::Clone::clone(&#place.#ident) })),
+            CaptureField::Range {
+                is_required: true,
+                is_pair: true,
+            } => output.push((
+                name,
+                quote! {{
+                    let (start, end) = ::core::clone::Clone::clone(&#place.#ident);
+                    ::core::option::Option::Some(start..end)
+                }},
+            )),
+            CaptureField::Range {
+                is_required: false,
+                is_pair: true,
+            } => output.push((
+                name,
+                quote! {
+                    ::core::clone::Clone::clone(&#place.#ident).map(|(start, end)| start..end)
+                },
+            )),
+            CaptureField::Count => (),
+            CaptureField::Struct(fields) => capture_names(
+                fields,
+                &quote! { #place.#ident },
+                &format!("{name}."),
+                output,
+            ),
+            CaptureField::Variants {
+                enum_ident,
+                variants,
+            } => {
+                for (variant_ident, fields) in variants {
+                    let mut variant_output = vec![];
+                    capture_names(
+                        fields,
+                        &quote! { capture },
+                        &format!("{name}.{variant_ident}."),
+                        &mut variant_output,
+                    );
+                    output.extend(variant_output.into_iter().map(|(name, get)| {
+                        (
+                            name,
+                            quote! {
+                                match &#place.#ident {
+                                    ::core::option::Option::Some(
+                                        #enum_ident::#variant_ident(capture),
+                                    ) => #get,
+                                    _ => ::core::option::Option::None,
+                                }
+                            },
+                        )
+                    }));
+                }
+            }
+        }
+    }
+}
+
+fn capture_impl_captures(
+    hitori_ident: &Ident,
+    idx_ident: &Ident,
+    fields: &CaptureFields,
+) -> TokenStream {
+    let ident = &fields.ident;
+    let mut names = vec![];
+    capture_names(fields, &quote! { self }, "", &mut names);
+    let (names, gets): (Vec<_>, Vec<_>) = names.into_iter().unzip();
+    quote! {
+        impl<#idx_ident: ::core::clone::Clone> #hitori_ident::Captures<#idx_ident>
+        for #ident<#idx_ident>
+        {
+            const NAMES: &'static [&'static str] = &[#(#names),*];
+
+            #[allow(unused_variables)]
+            fn get(&self, name: &str) -> ::core::option::Option<::core::ops::Range<#idx_ident>> {
+                match name {
+                    #(#names => #gets,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    }
+}
+
+fn capture_impl_map_idx(
+    hitori_ident: &Ident,
+    idx_ident: &Ident,
+    fields: &CaptureFields,
+) -> TokenStream {
+    let ident = &fields.ident;
+    let new_idx_ident = format_ident!("New{idx_ident}");
+    let map_idx = quote! { #hitori_ident::MapIdx::<#idx_ident, #new_idx_ident>::map_idx };
+    let block = if fields.fields.is_empty() {
+        quote! {( ::core::marker::PhantomData )}
+    } else {
+        let field_idents = fields.fields.keys();
+        let field_values = fields.fields.iter().map(|(ident, field)| match field {
+            CaptureField::Range {
+                is_required: true,
+                is_pair: false,
+            } => quote! { f(self.#ident.start)..f(self.#ident.end) },
+            CaptureField::Range {
+                is_required: false,
+                is_pair: false,
+            } => quote! { self.#ident.map(|range| f(range.start)..f(range.end)) },
+            CaptureField::Range {
+                is_required: true,
+                is_pair: true,
+            } => quote! { (f(self.#ident.0), f(self.#ident.1)) },
+            CaptureField::Range {
+                is_required: false,
+                is_pair: true,
+            } => quote! { self.#ident.map(|(start, end)| (f(start), f(end))) },
+            CaptureField::Count => quote! { self.#ident },
+            CaptureField::Struct(_) => quote! { #map_idx(self.#ident, &mut f) },
+            CaptureField::Variants {
+                enum_ident,
+                variants,
+            } => {
+                let variant_idents = variants.iter().map(|(ident, _)| ident);
+                quote! {
+                    self.#ident.map(|variant| match variant {
+                        #(
+                            #enum_ident::#variant_idents(capture) => {
+                                #enum_ident::#variant_idents(#map_idx(capture, &mut f))
+                            }
+                        )*
+                    })
+                }
+            }
+        });
+        let phantom_value = fields
+            .fields
+            .values()
+            .all(|field| matches!(field, CaptureField::Count))
+            .then(|| quote! { __phantom: ::core::marker::PhantomData, });
+        quote! {{
+            #(#field_idents: #field_values,)*
+            #phantom_value
+        }}
+    };
+    quote! {
+        impl<#idx_ident, #new_idx_ident> #hitori_ident::MapIdx<#idx_ident, #new_idx_ident>
+        for #ident<#idx_ident>
+        {
+            type Output = #ident<#new_idx_ident>;
+
+            #[allow(unused_mut, unused_variables)]
+            fn map_idx<F>(self, mut f: F) -> Self::Output
+            where
+                F: ::core::ops::FnMut(#idx_ident) -> #new_idx_ident,
+            {
+                #ident #block
+            }
+        }
+    }
+}
+
+fn capture_variants(
+    hitori_ident: &Ident,
+    vis: &Visibility,
+    attrs: &TokenStream,
+    enum_ident: &Ident,
+    idx_ident: &Ident,
+    default_idx_ty: Option<&Type>,
+    variants: &[(Ident, CaptureFields)],
+) -> TokenStream {
+    let variant_idents = variants.iter().map(|(ident, _)| ident);
+    let variant_struct_idents = variants.iter().map(|(_, fields)| &fields.ident);
+    let variant_docs = variants.iter().map(|(_, fields)| &fields.doc);
+    let idx_bound = idx_bound(idx_ident, default_idx_ty);
+    let mut output = quote! {
+        #[doc = "Matched branch of `#[hitori::variants]` group"]
+        #attrs
+        #vis enum #enum_ident<#idx_bound> {
+            #(
+                #[doc = #variant_docs]
+                #variant_idents(#variant_struct_idents<#idx_ident>),
+            )*
+        }
+    };
+    for (_, fields) in variants {
+        output.extend(capture(
+            hitori_ident,
+            vis,
+            attrs,
+            idx_ident,
+            default_idx_ty,
+            fields,
+        ));
+    }
+    output
 }
 
 fn capture(
+    hitori_ident: &Ident,
     vis: &Visibility,
-    ident: &Ident,
+    attrs: &TokenStream,
     idx_ident: &Ident,
     default_idx_ty: Option<&Type>,
-    field_idents: &BTreeSet<Ident>,
+    fields: &CaptureFields,
 ) -> TokenStream {
-    let (members, default_block, doc) = if field_idents.is_empty() {
+    let ident = &fields.ident;
+    let mut nested = TokenStream::new();
+    let mut doc = fields.doc.clone();
+    let (members, default_block) = if fields.fields.is_empty() {
+        doc.push_str("\n\nThe pattern has no captures, so this is an empty placeholder-struct.");
         (
             quote! {( ::core::marker::PhantomData<#idx_ident> );},
             quote! {( ::core::marker::PhantomData )},
-            Some(quote! { #[doc = "This is an empty placeholder-struct"] }),
         )
     } else {
+        let field_idents: Vec<_> = fields.fields.keys().collect();
+        let field_docs = fields.fields.keys().map(|ident| {
+            fields
+                .field_docs
+                .get(ident)
+                .map_or_else(String::new, FieldDocs::to_doc)
+        });
+        let (field_tys, field_defaults): (Vec<_>, Vec<_>) = fields
+            .fields
+            .values()
+            .map(|field| match field {
+                CaptureField::Range {
+                    is_required: true,
+                    is_pair: false,
+                } => (
+                    quote! { ::core::ops::Range<#idx_ident> },
+                    quote! {
+                        ::core::default::Default::default()..::core::default::Default::default()
+                    },
+                ),
+                CaptureField::Range {
+                    is_required: false,
+                    is_pair: false,
+                } => (
+                    quote! { ::core::option::Option<::core::ops::Range<#idx_ident>> },
+                    quote! { ::core::option::Option::None },
+                ),
+                CaptureField::Range {
+                    is_required: true,
+                    is_pair: true,
+                } => (
+                    quote! { (#idx_ident, #idx_ident) },
+                    quote! { ::core::default::Default::default() },
+                ),
+                CaptureField::Range {
+                    is_required: false,
+                    is_pair: true,
+                } => (
+                    quote! { ::core::option::Option<(#idx_ident, #idx_ident)> },
+                    quote! { ::core::option::Option::None },
+                ),
+                CaptureField::Count => (quote! { usize }, quote! { 0 }),
+                CaptureField::Struct(fields) => {
+                    nested.extend(capture(
+                        hitori_ident,
+                        vis,
+                        attrs,
+                        idx_ident,
+                        default_idx_ty,
+                        fields,
+                    ));
+                    let nested_ident = &fields.ident;
+                    (
+                        quote! { #nested_ident<#idx_ident> },
+                        quote! { ::core::default::Default::default() },
+                    )
+                }
+                CaptureField::Variants {
+                    enum_ident,
+                    variants,
+                } => {
+                    nested.extend(capture_variants(
+                        hitori_ident,
+                        vis,
+                        attrs,
+                        enum_ident,
+                        idx_ident,
+                        default_idx_ty,
+                        variants,
+                    ));
+                    (
+                        quote! { ::core::option::Option<#enum_ident<#idx_ident>> },
+                        quote! { ::core::option::Option::None },
+                    )
+                }
+            })
+            .unzip();
+        let (phantom_member, phantom_default) = if fields
+            .fields
+            .values()
+            .all(|field| matches!(field, CaptureField::Count))
+        {
+            (
+                Some(quote! { __phantom: ::core::marker::PhantomData<#idx_ident>, }),
+                Some(quote! { __phantom: ::core::marker::PhantomData, }),
+            )
+        } else {
+            (None, None)
+        };
         (
             quote! {{
                 #(
-                    #vis #field_idents: ::core::option::Option<::core::ops::Range<#idx_ident>>,
+                    #[doc = #field_docs]
+                    #vis #field_idents: #field_tys,
                 )*
+                #phantom_member
             }},
             quote! {{
                 #(
-                    #field_idents: ::core::option::Option::None,
+                    #field_idents: #field_defaults,
                 )*
+                #phantom_default
             }},
-            None,
         )
     };
-    let idx_bound = default_idx_ty.map_or_else(
-        || idx_ident.to_token_stream(),
-        |ty| quote! { #idx_ident = #ty },
-    );
+    let idx_bound = idx_bound(idx_ident, default_idx_ty);
+    let default_idx_bound = if fields.is_default_idx_required() {
+        quote! { #idx_ident: ::core::default::Default }
+    } else {
+        idx_ident.to_token_stream()
+    };
+    let impl_captures = capture_impl_captures(hitori_ident, idx_ident, fields);
+    let impl_map_idx = capture_impl_map_idx(hitori_ident, idx_ident, fields);
     quote! {
-        #doc
-        #[derive(
-            ::core::clone::Clone,
-            ::core::cmp::Eq,
-            ::core::cmp::PartialEq,
-            ::core::fmt::Debug,
-        )]
+        #[doc = #doc]
+        #attrs
         #vis struct #ident<#idx_bound> #members
-        impl<#idx_ident> ::core::default::Default for #ident<#idx_ident> {
+        impl<#default_idx_bound> ::core::default::Default for #ident<#idx_ident> {
             fn default() -> Self {
                 Self #default_block
             }
         }
+        #impl_captures
+        #impl_map_idx
+        #nested
+    }
+}
+
+fn min_len_fn(hitori_ident: &Ident, idx_ty: &Type, ch_ty: &Type) -> TokenStream {
+    quote! {
+        #[inline]
+        fn min_len(&self) -> usize {
+            <Self as #hitori_ident::ExprLen<#idx_ty, #ch_ty>>::MIN_LEN
+        }
+    }
+}
+
+fn impl_expr_len(
+    hitori_ident: &Ident,
+    self_ty: &Type,
+    idx_ty: &Type,
+    ch_ty: &Type,
+    generic_params: &Punctuated<GenericParam, Token![,]>,
+    where_clause: Option<&WhereClause>,
+    (min_len, max_len): (usize, Option<usize>),
+) -> TokenStream {
+    let max_len = max_len.map_or_else(
+        || quote! { ::core::option::Option::None },
+        |max_len| quote! { ::core::option::Option::Some(#max_len) },
+    );
+    quote! {
+        impl<#generic_params> #hitori_ident::ExprLen<#idx_ty, #ch_ty> for #self_ty
+        #where_clause
+        {
+            const MIN_LEN: usize = #min_len;
+            const MAX_LEN: ::core::option::Option<usize> = #max_len;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn impl_expr_ast(
+    hitori_ident: &Ident,
+    self_ty: &Type,
+    idx_ty: &Type,
+    ch_ty: &Type,
+    generic_params: &Punctuated<GenericParam, Token![,]>,
+    where_clause: Option<&WhereClause>,
+    node: &TokenStream,
+    pattern_string: &str,
+) -> TokenStream {
+    quote! {
+        impl<#generic_params> #hitori_ident::ast::ExprAst<#idx_ty, #ch_ty> for #self_ty
+        #where_clause
+        {
+            const AST: #hitori_ident::ast::Node = #node;
+
+            #[inline]
+            fn pattern_string() -> &'static str {
+                #pattern_string
+            }
+        }
+    }
+}
+
+fn derived_impl_expr_mut_is_match_block(
+    hitori_ident: &Ident,
+    idx_ty: &Type,
+    ch_ty: &Type,
+) -> TokenStream {
+    quote! {
+        <Self as #hitori_ident::Expr<#idx_ty, #ch_ty>>::is_match(self, start, is_first, iter)
     }
 }
 
@@ -115,41 +572,108 @@ fn derived_impl_expr_mut_starts_with_block(
 
 pub fn expand(parsed: parse::Output) -> syn::Result<TokenStream> {
     let hitori_ident = hitori_ident();
+    let trait_args = {
+        let idx_ty = &parsed.idx_ty;
+        let ch_ty = &parsed.ch_ty;
+        let ctx_ty = parsed.ctx_ty.iter();
+        quote! { #idx_ty, #ch_ty #(, #ctx_ty)* }
+    };
     let impl_decl = |trait_ident| {
         impl_decl(
             &hitori_ident,
             &parsed.self_ty,
             trait_ident,
-            &parsed.idx_ty,
-            &parsed.ch_ty,
+            &trait_args,
             &parsed.generic_params,
             parsed.where_clause.as_ref(),
         )
     };
     let starts_with_sig = |is_mut, inline| {
-        let starts_with_ident = if is_mut {
-            format_ident!("starts_with_mut")
+        let is_try = is_mut && parsed.error_ty.is_some();
+        let (starts_with_ident, capture_trait_ident) = if is_try {
+            (
+                format_ident!("try_starts_with_mut"),
+                format_ident!("TryExprMut"),
+            )
+        } else if is_mut {
+            (format_ident!("starts_with_mut"), format_ident!("ExprMut"))
+        } else if parsed.ctx_ty.is_some() {
+            (format_ident!("starts_with_ctx"), format_ident!("ExprWith"))
         } else {
-            format_ident!("starts_with")
+            (format_ident!("starts_with"), format_ident!("ExprMut"))
         };
         starts_with_sig(
             &hitori_ident,
             is_mut,
             &starts_with_ident,
+            &quote! { #capture_trait_ident<#trait_args> },
             &parsed.iter_ident,
             &parsed.idx_ty,
             &parsed.ch_ty,
+            parsed.ctx_ty.as_ref(),
+            is_try,
             inline,
         )
     };
 
-    let type_capture = type_capture(&parsed.capture_ident, &parsed.idx_ty);
-    let (mut output, impl_decl, type_capture, starts_with_sig) = if parsed.is_mut {
+    let capture_ty = capture_ty(
+        &parsed.capture_ident,
+        parsed.capture_sink.as_ref(),
+        &parsed.idx_ty,
+    );
+    let type_capture = type_capture(&capture_ty);
+    let starts_with_block::Output {
+        tokens: starts_with_block,
+        is_match_tokens,
+        mut capture_fields,
+    } = starts_with_block::Input {
+        hitori_ident: &hitori_ident,
+        is_mut: parsed.is_mut,
+        capture_ident: &parsed.capture_ident,
+        capture_ty: &capture_ty,
+        is_capture_sink: parsed.capture_sink.is_some(),
+        is_capture_pairs: parsed.is_capture_pairs,
+        self_ty: &parsed.self_ty,
+        iter_ident: &parsed.iter_ident,
+        idx_ty: &parsed.idx_ty,
+        ch_ty: &parsed.ch_ty,
+        ctx_ty: parsed.ctx_ty.as_ref(),
+        error_ty: parsed.error_ty.as_ref().map(|item| &item.ty),
+        expr: &parsed.expr,
+        is_test_by_ref: parsed.is_test_by_ref,
+        transactional: parsed.transactional,
+        automaton: parsed.automaton.as_ref(),
+        wrapper_ident: &parsed.wrapper_ident,
+        generic_params: &parsed.generic_params,
+        where_clause: parsed.where_clause.as_ref(),
+    }
+    .expand()?;
+    let mut find_hint_fns = min_len_fn(&hitori_ident, &parsed.idx_ty, &parsed.ch_ty);
+    find_hint_fns.extend(starts_with_block::can_start_with_fn(
+        &parsed.ch_ty,
+        &parsed.expr,
+        parsed.is_test_by_ref,
+    )?);
+    find_hint_fns.extend(starts_with_block::literal_fn(
+        &hitori_ident,
+        &parsed.expr,
+        parsed.is_test_by_ref,
+    )?);
+    let (mut output, impl_decl, type_capture, starts_with_sig, find_hint_fns) = if parsed.is_mut {
         (
             TokenStream::new(),
             impl_decl(&parsed.trait_ident),
             Some(type_capture),
             starts_with_sig(true, false),
+            Some(find_hint_fns),
+        )
+    } else if parsed.ctx_ty.is_some() {
+        (
+            TokenStream::new(),
+            impl_decl(&parsed.trait_ident),
+            Some(type_capture),
+            starts_with_sig(false, false),
+            Some(find_hint_fns),
         )
     } else {
         let impl_expr_decl = impl_decl(&parsed.trait_ident);
@@ -157,51 +681,98 @@ pub fn expand(parsed: parse::Output) -> syn::Result<TokenStream> {
         let impl_expr_mut_starts_with_sig = starts_with_sig(true, true);
         let impl_expr_mut_starts_with_block =
             derived_impl_expr_mut_starts_with_block(&hitori_ident, &parsed.idx_ty, &parsed.ch_ty);
+        let impl_expr_mut_is_match_fn = is_match_tokens.is_some().then(|| {
+            let sig = is_match_sig(
+                true,
+                &parsed.iter_ident,
+                &parsed.idx_ty,
+                &parsed.ch_ty,
+                true,
+            );
+            let block =
+                derived_impl_expr_mut_is_match_block(&hitori_ident, &parsed.idx_ty, &parsed.ch_ty);
+            quote! { #sig { #block } }
+        });
         (
             quote! {
                 #impl_expr_mut_decl {
                     #type_capture
                     #impl_expr_mut_starts_with_sig { #impl_expr_mut_starts_with_block }
+                    #impl_expr_mut_is_match_fn
+                    #find_hint_fns
                 }
             },
             impl_expr_decl,
             None,
             starts_with_sig(false, false),
+            None,
         )
     };
 
-    let starts_with_block::Output {
-        tokens: starts_with_block,
-        inner_capture_idents,
-    } = starts_with_block::Input {
-        hitori_ident: &hitori_ident,
-        is_mut: parsed.is_mut,
-        capture_ident: &parsed.capture_ident,
-        self_ty: &parsed.self_ty,
-        iter_ident: &parsed.iter_ident,
-        idx_ty: &parsed.idx_ty,
-        ch_ty: &parsed.ch_ty,
-        expr: &parsed.expr,
-        wrapper_ident: &parsed.wrapper_ident,
-        generic_params: parsed.generic_params,
-        where_clause: parsed.where_clause.as_ref(),
-    }
-    .expand()?;
+    output.extend(impl_expr_len(
+        &hitori_ident,
+        &parsed.self_ty,
+        &parsed.idx_ty,
+        &parsed.ch_ty,
+        &parsed.generic_params,
+        parsed.where_clause.as_ref(),
+        starts_with_block::len_bounds(&parsed.expr)?,
+    ));
+    output.extend(impl_expr_ast(
+        &hitori_ident,
+        &parsed.self_ty,
+        &parsed.idx_ty,
+        &parsed.ch_ty,
+        &parsed.generic_params,
+        parsed.where_clause.as_ref(),
+        &starts_with_block::ast_node(&hitori_ident, &parsed.expr)?,
+        &starts_with_block::pattern_string(&parsed.expr)?,
+    ));
 
+    let impl_doc = starts_with_block::impl_doc(&parsed.expr)?;
+
+    let is_match_fn = is_match_tokens.map(|block| {
+        let sig = is_match_sig(
+            parsed.is_mut,
+            &parsed.iter_ident,
+            &parsed.idx_ty,
+            &parsed.ch_ty,
+            false,
+        );
+        quote! { #sig { #block } }
+    });
+    let reset_fn = &parsed.reset_fn;
+    let error_ty = &parsed.error_ty;
     output.extend(quote! {
+        #[doc = #impl_doc]
         #impl_decl {
             #type_capture
+            #error_ty
             #starts_with_sig { #starts_with_block }
+            #is_match_fn
+            #reset_fn
+            #find_hint_fns
         }
     });
 
-    output.extend(capture(
-        &parsed.capture_vis,
-        &parsed.capture_ident,
-        &parsed.capture_idx_ident,
-        (!parsed.is_idx_generic).then_some(&parsed.idx_ty),
-        &inner_capture_idents,
-    ));
+    if parsed.capture_sink.is_none() {
+        capture_fields.doc = format!(
+            "Capture of `{}` expression",
+            parsed
+                .self_ty
+                .to_token_stream()
+                .to_string()
+                .replace(' ', "")
+        );
+        output.extend(capture(
+            &hitori_ident,
+            &parsed.capture_vis,
+            &capture_attrs(&parsed.capture_derives, &parsed.capture_attrs),
+            &parsed.capture_idx_ident,
+            (!parsed.is_idx_generic).then_some(&parsed.idx_ty),
+            &capture_fields,
+        ));
+    }
 
     Ok(output)
 }
@@ -1,30 +1,45 @@
+mod ast;
 mod cache;
+mod can_start_with;
+mod capture_fields;
+mod dfa;
+mod docs;
+mod len;
+mod lint;
+mod literal;
+mod pattern_string;
 mod repeat;
+mod required;
 mod state;
 
 use crate::{
-    parse::{position::Position, repeat::Repeat},
+    parse::{
+        capture::Capture, position::Position, repeat::Repeat, variants::Variants, Transactional,
+    },
     utils::{
-        eq_by_fmt, find_le_one_hitori_attr, hitori_attr_ident_eq_str,
-        lifetimes_into_punctuated_unit_refs, remove_generic_params_bounds,
+        copy_generic_params, eq_by_fmt, expr_try_from_lit_str_expr, find_le_one_hitori_attr,
+        hitori_attr_ident_eq_str, lifetimes_into_punctuated_unit_refs,
+        remove_generic_params_bounds,
     },
 };
 use proc_macro2::{Ident, TokenStream};
-use quote::{quote, ToTokens as _};
+use quote::{format_ident, quote, ToTokens as _};
 use state::State;
-use std::collections::BTreeSet;
 use syn::{punctuated::Punctuated, Attribute, Expr, GenericParam, Token, Type, WhereClause};
 
 #[allow(clippy::too_many_arguments)]
 fn partial_impl_wrapper(
     is_mut: bool,
-    capture_ident: &Ident,
+    capture_ty: Option<&TokenStream>,
+    actions_ty: Option<&TokenStream>,
     self_ty: &Type,
     iter_ident: &Ident,
     idx_ty: &Type,
     ch_ty: &Type,
+    ctx_ty: Option<&Type>,
+    error_ty: Option<&Type>,
     wrapper_ident: &Ident,
-    mut generic_params: Punctuated<GenericParam, Token![,]>,
+    generic_params: &Punctuated<GenericParam, Token![,]>,
     where_clause: Option<&WhereClause>,
 ) -> TokenStream {
     fn wrapper_params(
@@ -39,7 +54,7 @@ fn partial_impl_wrapper(
         output
     }
 
-    let maybe_bounds_wrapper_params = wrapper_params(&generic_params, iter_ident);
+    let maybe_bounds_wrapper_params = wrapper_params(generic_params, iter_ident);
 
     let mut phantom_data_params = lifetimes_into_punctuated_unit_refs(
         generic_params
@@ -51,6 +66,7 @@ fn partial_impl_wrapper(
             }),
     );
 
+    let mut generic_params = copy_generic_params(generic_params);
     remove_generic_params_bounds(&mut generic_params);
     let no_bounds_wrapper_params = wrapper_params(&generic_params, iter_ident);
 
@@ -78,14 +94,21 @@ fn partial_impl_wrapper(
     };
 
     let mut_ = is_mut.then_some(<Token![mut]>::default());
+    let capture_field = capture_ty.map(|ty| quote! { __capture: #ty, });
+    let actions_field = actions_ty.map(|ty| quote! { __actions: #ty, });
+    let ctx_field = ctx_ty.map(|ty| quote! { __ctx: #ty, });
+    let error_field = error_ty.map(|ty| quote! { __error: ::core::option::Option<#ty>, });
 
     let mut output = quote! {
        struct #wrapper_ident<#maybe_bounds_wrapper_params> #where_clause {
            __target: &'__self #mut_ #self_ty,
-           __capture: #capture_ident<#idx_ty>,
+           #capture_field
            __end: #idx_ty,
            __is_first: bool,
            __iter: #iter_ident,
+           #ctx_field
+           #error_field
+           #actions_field
            __phantom: ::core::marker::PhantomData<(#phantom_data_params)>,
        };
 
@@ -123,23 +146,43 @@ fn partial_impl_wrapper(
 
 enum HitoriAttribute {
     Repeat(Repeat),
-    Capture(Punctuated<Ident, Token![,]>),
+    Capture(Capture),
+    CaptureStruct(Ident),
+    Variants(Variants),
+    Count(Ident),
     Position(Position),
+    WithIndex,
+    ByRef,
+    OnMatch(Expr),
 }
 
 impl HitoriAttribute {
     fn find(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
         match find_le_one_hitori_attr(attrs) {
             Ok(Some(attr)) => Ok(Some(if hitori_attr_ident_eq_str(attr, "capture") {
-                let capture_idents = attr.parse_args_with(Punctuated::parse_terminated)?;
-                if capture_idents.is_empty() {
+                let capture: Capture = attr.parse_args()?;
+                if capture.idents.is_empty() {
                     return Err(syn::Error::new_spanned(
                         attr,
                         "capture must contain at least one identifier \
                         (e.g. `#[hitori::capture(this)]`)",
                     ));
                 }
-                Self::Capture(capture_idents)
+                Self::Capture(capture)
+            } else if hitori_attr_ident_eq_str(attr, "capture_struct") {
+                Self::CaptureStruct(attr.parse_args()?)
+            } else if hitori_attr_ident_eq_str(attr, "variants") {
+                let variants: Variants = attr.parse_args()?;
+                if variants.idents.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "variants must contain at least one identifier \
+                        (e.g. `#[hitori::variants(A, B)]`)",
+                    ));
+                }
+                Self::Variants(variants)
+            } else if hitori_attr_ident_eq_str(attr, "count") {
+                Self::Count(attr.parse_args()?)
             } else if hitori_attr_ident_eq_str(attr, "repeat") {
                 let tokens = &attr.meta.require_list()?.tokens;
                 if tokens.is_empty() || eq_by_fmt(tokens, quote! { () }) {
@@ -152,10 +195,20 @@ impl HitoriAttribute {
                 Self::Repeat(attr.parse_args()?)
             } else if hitori_attr_ident_eq_str(attr, "position") {
                 Self::Position(attr.parse_args()?)
+            } else if hitori_attr_ident_eq_str(attr, "with_index") {
+                attr.meta.require_path_only()?;
+                Self::WithIndex
+            } else if hitori_attr_ident_eq_str(attr, "by_ref") {
+                attr.meta.require_path_only()?;
+                Self::ByRef
+            } else if hitori_attr_ident_eq_str(attr, "on_match") {
+                Self::OnMatch(expr_try_from_lit_str_expr(attr.parse_args()?)?)
             } else {
                 return Err(syn::Error::new_spanned(
                     attr,
-                    "expected `capture`, or `repeat`, or `position`",
+                    "expected `capture`, or `capture_struct`, or `variants`, or `count`, \
+                    or `repeat`, or `position`, or `with_index`, or `by_ref`, \
+                    or `on_match`",
                 ));
             })),
             Ok(None) => Ok(None),
@@ -198,6 +251,18 @@ enum Tree<'a> {
     Test(&'a Expr),
 }
 
+fn has_hitori_attr(expr: &Expr, ident_str: &str) -> bool {
+    let (attrs, elems) = match expr {
+        Expr::Tuple(tuple) => (&tuple.attrs, &tuple.elems),
+        Expr::Array(arr) => (&arr.attrs, &arr.elems),
+        _ => return false,
+    };
+    matches!(
+        find_le_one_hitori_attr(attrs),
+        Ok(Some(attr)) if hitori_attr_ident_eq_str(attr, ident_str)
+    ) || elems.iter().any(|elem| has_hitori_attr(elem, ident_str))
+}
+
 impl<'a> TryFrom<&'a Expr> for Tree<'a> {
     type Error = syn::Error;
 
@@ -221,70 +286,249 @@ impl<'a> TryFrom<&'a Expr> for Tree<'a> {
     }
 }
 
+pub use capture_fields::{CaptureFields, Field as CaptureField, FieldDocs};
+
 pub struct Output {
     pub tokens: TokenStream,
-    pub inner_capture_idents: BTreeSet<Ident>,
+    /// Body of `is_match` or `is_match_mut`, if the expression captures anything
+    pub is_match_tokens: Option<TokenStream>,
+    pub capture_fields: CaptureFields,
+}
+
+/// *See [`ast::node`]*
+pub fn ast_node(hitori_ident: &Ident, expr: &Expr) -> syn::Result<TokenStream> {
+    ast::node(hitori_ident, expr.try_into()?)
+}
+
+pub use can_start_with::can_start_with_fn;
+pub use docs::impl_doc;
+pub use literal::literal_fn;
+pub use pattern_string::pattern_string;
+
+/// *See [`len::bounds`]*
+pub fn len_bounds(expr: &Expr) -> syn::Result<len::Bounds> {
+    len::bounds(expr.try_into()?)
 }
 
 pub struct Input<'a> {
     pub hitori_ident: &'a Ident,
     pub is_mut: bool,
     pub capture_ident: &'a Ident,
+    pub capture_ty: &'a TokenStream,
+    pub is_capture_sink: bool,
+    pub is_capture_pairs: bool,
     pub self_ty: &'a Type,
     pub iter_ident: &'a Ident,
     pub idx_ty: &'a Type,
     pub ch_ty: &'a Type,
+    pub ctx_ty: Option<&'a Type>,
+    pub error_ty: Option<&'a Type>,
     pub expr: &'a Expr,
+    pub is_test_by_ref: bool,
+    pub transactional: Option<Transactional>,
+    pub automaton: Option<&'a Ident>,
     pub wrapper_ident: &'a Ident,
-    pub generic_params: Punctuated<GenericParam, Token![,]>,
+    pub generic_params: &'a Punctuated<GenericParam, Token![,]>,
     pub where_clause: Option<&'a WhereClause>,
 }
 
+fn target_cache_restore(
+    target: Option<&cache::Target>,
+) -> (Option<TokenStream>, Option<TokenStream>) {
+    // Deferred actions are dropped with the wrapper, so `self` is untouched
+    let target = target.filter(|target| !matches!(target, cache::Target::Deferred));
+    target.map_or((None, None), |target| {
+        let saved = format_ident!("target");
+        let save = target.save(&quote! { self });
+        (
+            Some(quote! { let #saved = #save; }),
+            Some(target.restore(&quote! { wrapper.__target }, &saved)),
+        )
+    })
+}
+
+/// Field initializer and the call running deferred `on_match` actions
+fn actions_init_run(has_actions: bool) -> (Option<TokenStream>, Option<TokenStream>) {
+    if has_actions {
+        (
+            Some(quote! { __actions: ::core::default::Default::default(), }),
+            Some(quote! { wrapper.__run_actions(); }),
+        )
+    } else {
+        (None, None)
+    }
+}
+
 impl<'a> Input<'a> {
-    pub fn expand(self) -> syn::Result<Output> {
-        let mut st = State::default();
-        let inner_capture_idents = st.push_tree(self.expr.try_into()?)?;
+    fn state(&self, target: Option<&cache::Target>) -> State {
+        State::new(
+            self.capture_ident.clone(),
+            self.is_capture_sink.then(|| self.hitori_ident.clone()),
+            self.is_capture_pairs,
+            self.is_mut,
+            target.cloned(),
+            self.ctx_ty.is_some(),
+            self.error_ty.is_some(),
+            self.is_test_by_ref,
+        )
+    }
+
+    fn partial_impl_wrapper(
+        &self,
+        capture_ty: Option<&TokenStream>,
+        target: Option<&cache::Target>,
+    ) -> TokenStream {
         let hitori_ident = self.hitori_ident;
-        let partial_impl_wrapper = partial_impl_wrapper(
+        let idx_ty = self.idx_ty;
+        let actions_ty = matches!(target, Some(cache::Target::Deferred)).then(|| {
+            quote! { #hitori_ident::__private::Vec<(usize, ::core::ops::Range<#idx_ty>)> }
+        });
+        partial_impl_wrapper(
             self.is_mut,
-            self.capture_ident,
+            capture_ty,
+            actions_ty.as_ref(),
             self.self_ty,
             self.iter_ident,
             self.idx_ty,
             self.ch_ty,
+            self.ctx_ty,
+            self.error_ty,
             self.wrapper_ident,
             self.generic_params,
             self.where_clause,
-        );
+        )
+    }
+
+    /// Matches like `starts_with` block, but without a capture
+    /// and its caching, returning whether the expression matched
+    fn is_match_block(&self, target: Option<&cache::Target>) -> syn::Result<TokenStream> {
+        let mut st = self.state(target).without_captures();
+        st.push_tree(self.expr.try_into()?)?;
+        let partial_impl_wrapper = self.partial_impl_wrapper(None, target);
+        let run_actions_fn = st.run_actions_fn();
+        let (actions_init, run_actions) = actions_init_run(run_actions_fn.is_some());
         let impl_wrapper_block = st.impl_wrapper_block;
         let total_matches_ident = st.prev_subexpr_matches_ident.unwrap();
         let wrapper_ident = self.wrapper_ident;
+        let (target_cache, target_restore) = target_cache_restore(target);
+        Ok(quote! {
+            #partial_impl_wrapper {
+                #impl_wrapper_block
+                #run_actions_fn
+            }
+            #target_cache
+            let mut wrapper = #wrapper_ident {
+                __target: self,
+                __end: start,
+                __is_first: is_first,
+                __iter: ::core::iter::IntoIterator::into_iter(iter),
+                #actions_init
+                __phantom: ::core::marker::PhantomData,
+            };
+            let is_matched = wrapper.#total_matches_ident();
+            if is_matched {
+                #run_actions
+            } else {
+                #target_restore
+            }
+            is_matched
+        })
+    }
+
+    pub fn expand(self) -> syn::Result<Output> {
+        let target = if self.is_mut {
+            match self.transactional {
+                Some(Transactional::Clone) => Some(cache::Target::Clone),
+                Some(Transactional::Checkpoint) => {
+                    Some(cache::Target::Checkpoint(self.hitori_ident.clone()))
+                }
+                None => has_hitori_attr(self.expr, "on_match").then_some(cache::Target::Deferred),
+            }
+        } else {
+            None
+        };
+        let mut st = self.state(target.as_ref());
+        st.push_tree(self.expr.try_into()?)?;
+        st.check_required(required::guaranteed_capture_idents(self.expr.try_into()?)?)?;
+        if self.automaton.is_some() {
+            return Ok(Output {
+                tokens: dfa::Dfa::new(self.expr)?.starts_with_block(self.hitori_ident),
+                is_match_tokens: None,
+                capture_fields: st.capture_fields,
+            });
+        }
+        lint::check(self.expr.try_into()?, self.is_mut, self.is_test_by_ref)?;
+        let is_match_tokens = (self.ctx_ty.is_none()
+            && self.error_ty.is_none()
+            && !st.capture_fields.fields.is_empty())
+        .then(|| self.is_match_block(target.as_ref()))
+        .transpose()?;
+        let hitori_ident = self.hitori_ident;
+        let partial_impl_wrapper =
+            self.partial_impl_wrapper(Some(self.capture_ty), target.as_ref());
+        let run_actions_fn = st.run_actions_fn();
+        let (actions_init, run_actions) = actions_init_run(run_actions_fn.is_some());
+        let impl_wrapper_block = st.impl_wrapper_block;
+        let total_matches_ident = st.prev_subexpr_matches_ident.unwrap();
+        let wrapper_ident = self.wrapper_ident;
+        let (target_cache, target_restore) = target_cache_restore(target.as_ref());
+        let ctx_init = self.ctx_ty.map(|_| quote! { __ctx: ctx, });
+        let error_init = self
+            .error_ty
+            .map(|_| quote! { __error: ::core::option::Option::None, });
+        let mut matched = quote! {
+            ::core::option::Option::Some(#hitori_ident::Match {
+                range: start..wrapper.__end,
+                capture: wrapper.__capture,
+                iter_remainder: wrapper.__iter,
+                is_iter_advanced: !wrapper.__is_first,
+            })
+        };
+        let (not_matched, error_return) = if self.error_ty.is_some() {
+            matched = quote! { ::core::result::Result::Ok(#matched) };
+            (
+                quote! { ::core::result::Result::Ok(::core::option::Option::None) },
+                Some(quote! {
+                    if let ::core::option::Option::Some(err) = wrapper.__error {
+                        #target_restore
+                        return ::core::result::Result::Err(err);
+                    }
+                }),
+            )
+        } else {
+            (quote! { ::core::option::Option::None }, None)
+        };
         let tokens = quote! {
             #partial_impl_wrapper {
                 #impl_wrapper_block
+                #run_actions_fn
             }
+            #target_cache
             let mut wrapper = #wrapper_ident {
                 __target: self,
                 __capture: ::core::default::Default::default(),
                 __end: start.clone(),
                 __is_first: is_first,
                 __iter: ::core::iter::IntoIterator::into_iter(iter),
+                #ctx_init
+                #error_init
+                #actions_init
                 __phantom: ::core::marker::PhantomData,
             };
-            if wrapper.#total_matches_ident() {
-                ::core::option::Option::Some(#hitori_ident::Match {
-                    range: start..wrapper.__end,
-                    capture: wrapper.__capture,
-                    iter_remainder: wrapper.__iter,
-                    is_iter_advanced: !wrapper.__is_first,
-                })
+            let is_matched = wrapper.#total_matches_ident();
+            #error_return
+            if is_matched {
+                #run_actions
+                #matched
             } else {
-                ::core::option::Option::None
+                #target_restore
+                #not_matched
             }
         };
         Ok(Output {
             tokens,
-            inner_capture_idents,
+            is_match_tokens,
+            capture_fields: st.capture_fields,
         })
     }
 }
@@ -0,0 +1,119 @@
+use super::{Group, HitoriAttribute, Tree};
+use crate::{
+    parse::{capture::Keep, position::Position, repeat::Repeat},
+    utils::expr_eval_lit_usize,
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens as _};
+use syn::Expr;
+
+fn bound(hitori_ident: &Ident, expr: &Expr, is_excluded: bool) -> TokenStream {
+    let add = usize::from(is_excluded);
+    match expr_eval_lit_usize(expr).and_then(|lit| lit.checked_add(add)) {
+        Some(lit) => quote! { #hitori_ident::ast::Bound::Lit(#lit) },
+        None => {
+            let mut source = expr.to_token_stream().to_string();
+            if is_excluded {
+                source = format!("({source}) + 1");
+            }
+            quote! { #hitori_ident::ast::Bound::Expr(#source) }
+        }
+    }
+}
+
+fn group_node(hitori_ident: &Ident, group: &Group) -> syn::Result<TokenStream> {
+    let (variant, exprs) = match group {
+        Group::All(all) => (quote! { All }, all),
+        Group::Any(any) => (quote! { Any }, any),
+    };
+    let nodes = exprs
+        .iter()
+        .map(|expr| node(hitori_ident, expr.try_into()?))
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! { #hitori_ident::ast::Node::#variant(&[#(#nodes),*]) })
+}
+
+/// Expression of `hitori::ast::Node` type describing the tree
+pub fn node(hitori_ident: &Ident, tree: Tree) -> syn::Result<TokenStream> {
+    let node = quote! { #hitori_ident::ast::Node };
+    let (group, attr) = match tree {
+        Tree::Group(group, attr) => (group, attr),
+        Tree::Test(test) => {
+            let source = test.to_token_stream().to_string();
+            return Ok(quote! { #node::Test(#source) });
+        }
+    };
+    let inner = group_node(hitori_ident, &group)?;
+    let attr = match attr {
+        Some(attr) => attr,
+        None => return Ok(inner),
+    };
+    Ok(match attr {
+        HitoriAttribute::Repeat(repeat) => {
+            let (lo, hi) = match &repeat {
+                Repeat::Exact(exact) => (
+                    bound(hitori_ident, exact, false),
+                    Some(bound(hitori_ident, exact, true)),
+                ),
+                Repeat::InInclusive {
+                    lo_included,
+                    hi_excluded,
+                } => (
+                    bound(hitori_ident, lo_included, false),
+                    hi_excluded
+                        .as_ref()
+                        .map(|hi_excluded| bound(hitori_ident, hi_excluded, false)),
+                ),
+            };
+            let hi = hi.map_or_else(
+                || quote! { ::core::option::Option::None },
+                |hi| quote! { ::core::option::Option::Some(#hi) },
+            );
+            quote! { #node::Repeat { lo: #lo, hi: #hi, node: &#inner } }
+        }
+        HitoriAttribute::Capture(capture) => {
+            let names = capture.idents.iter().map(ToString::to_string);
+            let keep = match capture.keep {
+                Keep::First => quote! { First },
+                Keep::Last => quote! { Last },
+                Keep::Longest => quote! { Longest },
+            };
+            let is_required = capture.is_required;
+            quote! {
+                #node::Capture {
+                    names: &[#(#names),*],
+                    keep: #hitori_ident::ast::Keep::#keep,
+                    is_required: #is_required,
+                    node: &#inner,
+                }
+            }
+        }
+        HitoriAttribute::CaptureStruct(ident) => {
+            let name = ident.to_string();
+            quote! { #node::CaptureStruct { name: #name, node: &#inner } }
+        }
+        HitoriAttribute::Variants(variants) => {
+            let name = variants.dest.to_string();
+            let names = variants.idents.iter().map(ToString::to_string);
+            quote! { #node::Variants { name: #name, names: &[#(#names),*], node: &#inner } }
+        }
+        HitoriAttribute::Count(ident) => {
+            let name = ident.to_string();
+            quote! { #node::Count { name: #name, node: &#inner } }
+        }
+        HitoriAttribute::Position(position) => {
+            let (first, last) = match position {
+                Position::First => (true, false),
+                Position::Last => (false, true),
+                Position::FirstAndLast => (true, true),
+            };
+            quote! { #node::Position { first: #first, last: #last, node: &#inner } }
+        }
+        HitoriAttribute::WithIndex => quote! { #node::WithIndex(&#inner) },
+        HitoriAttribute::ByRef => quote! { #node::ByRef(&#inner) },
+        HitoriAttribute::OnMatch(action) => {
+            let action = action.to_token_stream().to_string();
+            quote! { #node::OnMatch { action: #action, node: &#inner } }
+        }
+    })
+}
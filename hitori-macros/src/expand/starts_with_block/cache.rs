@@ -2,51 +2,136 @@ use crate::utils::unique_ident;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 
-pub struct Capture<C>(C);
+pub struct Capture<'a, C> {
+    place: &'a TokenStream,
+    idents: C,
+    sink: Option<&'a Ident>,
+}
+
+impl<'a, 'b, C: Iterator<Item = &'b Ident> + Clone> Capture<'a, C> {
+    /// `sink` is the `hitori` crate identifier if captures are stored
+    /// in a [`CaptureSink`](hitori::CaptureSink) instead of a generated struct
+    pub fn new<I: IntoIterator<IntoIter = C>>(
+        place: &'a TokenStream,
+        capture_idents: I,
+        sink: Option<&'a Ident>,
+    ) -> Self {
+        Self {
+            place,
+            idents: capture_idents.into_iter(),
+            sink,
+        }
+    }
 
-impl<'a, C: Iterator<Item = &'a Ident> + Clone> Capture<C> {
-    pub fn new<I: IntoIterator<IntoIter = C>>(capture_idents: I) -> Self {
-        Self(capture_idents.into_iter())
+    fn is_empty(&self) -> bool {
+        self.idents.clone().next().is_none()
     }
 
     pub fn cache(&self) -> TokenStream {
-        let idents = self.0.clone();
+        let place = self.place;
+        if let Some(hitori_ident) = self.sink {
+            return if self.is_empty() {
+                TokenStream::new()
+            } else {
+                quote! {
+                    let capture_snapshot = #hitori_ident::CaptureSink::snapshot(&#place);
+                }
+            };
+        }
+        let idents = self.idents.clone();
         quote! {
             #(
                 let mut #idents =
-                    ::core::clone::Clone::clone(&self.__capture.#idents);
+                    ::core::clone::Clone::clone(&#place.#idents);
             )*
         }
     }
 
     pub fn restore(&self) -> TokenStream {
-        let idents = self.0.clone();
+        let place = self.place;
+        if let Some(hitori_ident) = self.sink {
+            return if self.is_empty() {
+                TokenStream::new()
+            } else {
+                quote! {
+                    #hitori_ident::CaptureSink::restore(&mut #place, capture_snapshot);
+                }
+            };
+        }
+        let idents = self.idents.clone();
         quote! {
             #(
-                self.__capture.#idents = #idents;
+                #place.#idents = #idents;
             )*
         }
     }
 }
 
+#[derive(Clone)]
+pub enum Target {
+    Clone,
+    Checkpoint(Ident),
+    /// The target is not changed until the match is confirmed, because
+    /// `on_match` actions are queued in `__actions` field of the wrapper,
+    /// so rolling back truncates the queue
+    Deferred,
+}
+
+impl Target {
+    pub fn save(&self, target: &TokenStream) -> TokenStream {
+        match self {
+            Self::Clone => quote! { ::core::clone::Clone::clone(&*#target) },
+            Self::Checkpoint(hitori_ident) => {
+                quote! { #hitori_ident::Checkpoint::checkpoint(&*#target) }
+            }
+            Self::Deferred => quote! { self.__actions.len() },
+        }
+    }
+
+    pub fn restore(&self, target: &TokenStream, saved: &Ident) -> TokenStream {
+        match self {
+            Self::Clone => quote! { *#target = #saved; },
+            Self::Checkpoint(hitori_ident) => {
+                quote! { #hitori_ident::Checkpoint::rollback(&mut *#target, #saved); }
+            }
+            Self::Deferred => quote! { self.__actions.truncate(#saved); },
+        }
+    }
+
+    fn restore_clone(&self, target: &TokenStream, saved: &Ident) -> TokenStream {
+        match self {
+            Self::Clone => quote! { *#target = ::core::clone::Clone::clone(&#saved); },
+            Self::Deferred => self.restore(target, saved),
+            Self::Checkpoint(_) => {
+                let restore = self.restore(target, saved);
+                let save = self.save(target);
+                quote! {
+                    #restore
+                    #saved = #save;
+                }
+            }
+        }
+    }
+}
+
 pub struct Vars {
     iter: Ident,
     is_first: Ident,
     end: Ident,
+    target: Option<(Ident, Target)>,
 }
 
-impl Default for Vars {
-    fn default() -> Self {
+impl Vars {
+    pub fn new(target: Option<&Target>) -> Self {
         Self {
             iter: format_ident!("iter"),
             is_first: format_ident!("is_first"),
             end: format_ident!("end"),
+            target: target.map(|target| (format_ident!("target"), target.clone())),
         }
     }
-}
 
-impl Vars {
-    pub fn unique_in<'a, I>(idents: I) -> Self
+    pub fn unique_in<'a, I>(idents: I, target: Option<&Target>) -> Self
     where
         I: IntoIterator<Item = &'a Ident>,
         I::IntoIter: Clone,
@@ -56,17 +141,33 @@ impl Vars {
             iter: unique_ident(&capture_idents, "iter".into()),
             is_first: unique_ident(&capture_idents, "is_first".into()),
             end: unique_ident(&capture_idents, "end".into()),
+            target: target.map(|target| {
+                (
+                    unique_ident(&capture_idents, "target".into()),
+                    target.clone(),
+                )
+            }),
         }
     }
 
+    fn target_save(&self) -> Option<(&Ident, TokenStream)> {
+        self.target
+            .as_ref()
+            .map(|(saved, target)| (saved, target.save(&quote! { self.__target })))
+    }
+
     pub fn cache(&self) -> TokenStream {
         let iter = &self.iter;
         let is_first = &self.is_first;
         let end = &self.end;
+        let target = self
+            .target_save()
+            .map(|(saved, save)| quote! { let mut #saved = #save; });
         quote! {
             let mut #iter = ::core::clone::Clone::clone(&self.__iter);
             let mut #is_first = self.__is_first;
             let mut #end = ::core::clone::Clone::clone(&self.__end);
+            #target
         }
     }
 
@@ -74,10 +175,14 @@ impl Vars {
         let iter = &self.iter;
         let is_first = &self.is_first;
         let end = &self.end;
+        let target = self
+            .target_save()
+            .map(|(saved, save)| quote! { #saved = #save; });
         quote! {
             #iter = ::core::clone::Clone::clone(&self.__iter);
             #is_first = self.__is_first;
             #end = ::core::clone::Clone::clone(&self.__end);
+            #target
         }
     }
 
@@ -85,10 +190,15 @@ impl Vars {
         let iter = &self.iter;
         let is_first = &self.is_first;
         let end = &self.end;
+        let target = self
+            .target
+            .as_ref()
+            .map(|(saved, target)| target.restore(&quote! { self.__target }, saved));
         quote! {
             self.__iter = #iter;
             self.__is_first = #is_first;
             self.__end = #end;
+            #target
         }
     }
 
@@ -96,10 +206,15 @@ impl Vars {
         let iter = &self.iter;
         let is_first = &self.is_first;
         let end = &self.end;
+        let target = self
+            .target
+            .as_ref()
+            .map(|(saved, target)| target.restore_clone(&quote! { self.__target }, saved));
         quote! {
             self.__iter = ::core::clone::Clone::clone(&#iter);
             self.__is_first = #is_first;
             self.__end = ::core::clone::Clone::clone(&#end);
+            #target
         }
     }
 }
@@ -0,0 +1,100 @@
+use super::{
+    pattern_string::{class_items, ClassItem},
+    Group, HitoriAttribute, Tree,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, Type};
+
+/// Checks of the characters the tree could start matching with
+/// (`None` if some of them are not known) and whether it could match nothing
+type Firsts = (Option<Vec<TokenStream>>, bool);
+
+/// Check of `ch: &Ch` equivalent to the test, if it is a simple test
+/// (see `pattern_string::class_items`)
+fn test_check(test: &Expr) -> Option<TokenStream> {
+    let checks = class_items(test)?.into_iter().map(|item| match item {
+        ClassItem::Char(_, lit) => quote! { *ch == #lit },
+        ClassItem::Range(_, _, range) => quote! { #range.contains(ch) },
+        ClassItem::Ranges(_, method) => quote! { ch.#method() },
+    });
+    Some(quote! { #(#checks)||* })
+}
+
+fn group_firsts(group: &Group) -> syn::Result<Firsts> {
+    match group {
+        Group::All(all) => {
+            let mut output = Some(vec![]);
+            for expr in all.iter() {
+                let (checks, is_nullable) = firsts(expr.try_into()?)?;
+                output = output.zip(checks).map(|(mut output, mut checks)| {
+                    output.append(&mut checks);
+                    output
+                });
+                if !is_nullable {
+                    return Ok((output, false));
+                }
+            }
+            Ok((output, true))
+        }
+        Group::Any(any) => {
+            let mut output = (Some(vec![]), false);
+            for expr in any.iter() {
+                let (checks, is_nullable) = firsts(expr.try_into()?)?;
+                output.0 = output.0.zip(checks).map(|(mut output, mut checks)| {
+                    output.append(&mut checks);
+                    output
+                });
+                output.1 |= is_nullable;
+            }
+            Ok(output)
+        }
+    }
+}
+
+fn firsts(tree: Tree) -> syn::Result<Firsts> {
+    match tree {
+        Tree::Group(group, attr) => match &attr {
+            Some(HitoriAttribute::Repeat(repeat)) => {
+                let (checks, is_nullable) = group_firsts(&group)?;
+                Ok((
+                    checks,
+                    is_nullable || repeat.lo_lit().map_or(true, |lo| lo == 0),
+                ))
+            }
+            // Tests are called with other arguments
+            Some(HitoriAttribute::WithIndex | HitoriAttribute::ByRef) => Ok((None, true)),
+            _ => group_firsts(&group),
+        },
+        Tree::Test(test) => Ok((test_check(test).map(|check| vec![check]), false)),
+    }
+}
+
+/// Implementation of `can_start_with` method, if every match starts with
+/// a character that passes one of the simple tests the pattern starts with
+pub fn can_start_with_fn(
+    ch_ty: &Type,
+    expr: &Expr,
+    is_test_by_ref: bool,
+) -> syn::Result<Option<TokenStream>> {
+    if is_test_by_ref {
+        return Ok(None);
+    }
+    Ok(match firsts(expr.try_into()?)? {
+        (Some(checks), false) => {
+            // An empty any-pattern never matches
+            let body = if checks.is_empty() {
+                quote! { false }
+            } else {
+                quote! { #((#checks))||* }
+            };
+            Some(quote! {
+                #[inline]
+                fn can_start_with(&self, ch: &#ch_ty) -> bool {
+                    #body
+                }
+            })
+        }
+        _ => None,
+    })
+}
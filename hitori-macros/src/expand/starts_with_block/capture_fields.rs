@@ -0,0 +1,217 @@
+use crate::utils::snake_to_upper_camel;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use std::{collections::BTreeMap, mem};
+
+pub enum Field {
+    Range {
+        is_required: bool,
+        /// Whether the range is stored as `(start, end)` pair
+        is_pair: bool,
+    },
+    Count,
+    Struct(CaptureFields),
+    Variants {
+        enum_ident: Ident,
+        variants: Vec<(Ident, CaptureFields)>,
+    },
+}
+
+impl Field {
+    fn is_required(&self) -> bool {
+        matches!(
+            self,
+            Self::Range {
+                is_required: true,
+                ..
+            }
+        )
+    }
+
+    pub fn attr_name(&self) -> &'static str {
+        match self {
+            Self::Range { .. } => "capture",
+            Self::Count => "count",
+            Self::Struct(_) => "capture_struct",
+            Self::Variants { .. } => "variants",
+        }
+    }
+}
+
+pub struct CaptureFields {
+    pub ident: Ident,
+    pub doc: String,
+    pub fields: BTreeMap<Ident, Field>,
+    pub field_docs: BTreeMap<Ident, FieldDocs>,
+}
+
+/// Documentation of a field
+#[derive(Default)]
+pub struct FieldDocs {
+    /// Descriptions of every place the field is captured at
+    places: Vec<String>,
+    /// Notes shared by the places, each mentioned once
+    notes: Vec<String>,
+}
+
+impl FieldDocs {
+    pub fn to_doc(&self) -> String {
+        let mut paragraphs = self.places.iter().chain(&self.notes);
+        let mut doc = paragraphs.next().cloned().unwrap_or_default();
+        for paragraph in paragraphs {
+            doc.push_str("\n\n");
+            doc.push_str(paragraph);
+        }
+        doc
+    }
+}
+
+impl CaptureFields {
+    pub fn new(ident: Ident, doc: String) -> Self {
+        Self {
+            ident,
+            doc,
+            fields: BTreeMap::new(),
+            field_docs: BTreeMap::new(),
+        }
+    }
+
+    pub fn nested_struct_ident(&self, field_ident: &Ident) -> Ident {
+        format_ident!("{}{}", self.ident, snake_to_upper_camel(field_ident))
+    }
+
+    pub fn insert(
+        &mut self,
+        ident: &Ident,
+        field: Field,
+        place: String,
+        notes: &[&str],
+    ) -> syn::Result<()> {
+        self.insert_field(ident, field)?;
+        let docs = self.field_docs.entry(ident.clone()).or_default();
+        if !docs.places.contains(&place) {
+            docs.places.push(place);
+        }
+        for note in notes {
+            if !docs.notes.iter().any(|existing| existing == note) {
+                docs.notes.push((*note).into());
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_field(&mut self, ident: &Ident, field: Field) -> syn::Result<()> {
+        match self.fields.get(ident) {
+            Some(existing) if mem::discriminant(existing) != mem::discriminant(&field) => {
+                Err(syn::Error::new_spanned(
+                    ident,
+                    format!("destination is already used by `{}`", existing.attr_name()),
+                ))
+            }
+            Some(Field::Range { is_required, .. }) if *is_required != field.is_required() => {
+                Err(syn::Error::new_spanned(
+                    ident,
+                    "destination has to be either `required` or not in every `capture`",
+                ))
+            }
+            Some(Field::Variants { .. }) => Err(syn::Error::new_spanned(
+                ident,
+                "destination is already used by another `variants` \
+                (e.g. use `#[hitori::variants(A, B, dest = other)]`)",
+            )),
+            Some(_) => Ok(()),
+            None => {
+                self.fields.insert(ident.clone(), field);
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether [`Default`] implementation needs `Idx: Default` bound
+    /// because of `required` fields in this or nested structs
+    pub fn is_default_idx_required(&self) -> bool {
+        self.fields.values().any(|field| match field {
+            Field::Range { is_required, .. } => *is_required,
+            Field::Struct(fields) => fields.is_default_idx_required(),
+            Field::Count | Field::Variants { .. } => false,
+        })
+    }
+
+    pub fn required_idents(&self) -> impl Iterator<Item = &Ident> {
+        self.fields
+            .iter()
+            .filter(|(_, field)| field.is_required())
+            .map(|(ident, _)| ident)
+    }
+
+    pub fn get(&self, path: &[Segment]) -> &Self {
+        let mut fields = self;
+        for segment in path {
+            fields = match (segment, fields.fields.get(segment.field_ident())) {
+                (Segment::Field(_), Some(Field::Struct(fields))) => fields,
+                (Segment::Variant { index, .. }, Some(Field::Variants { variants, .. })) => {
+                    &variants[*index].1
+                }
+                _ => unreachable!(),
+            };
+        }
+        fields
+    }
+
+    pub fn get_mut(&mut self, path: &[Segment]) -> &mut Self {
+        let mut fields = self;
+        for segment in path {
+            fields = match (segment, fields.fields.get_mut(segment.field_ident())) {
+                (Segment::Field(_), Some(Field::Struct(fields))) => fields,
+                (Segment::Variant { index, .. }, Some(Field::Variants { variants, .. })) => {
+                    &mut variants[*index].1
+                }
+                _ => unreachable!(),
+            };
+        }
+        fields
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Segment {
+    Field(Ident),
+    Variant {
+        field_ident: Ident,
+        enum_ident: Ident,
+        variant_ident: Ident,
+        index: usize,
+    },
+}
+
+impl Segment {
+    fn field_ident(&self) -> &Ident {
+        match self {
+            Self::Field(ident)
+            | Self::Variant {
+                field_ident: ident, ..
+            } => ident,
+        }
+    }
+}
+
+pub fn place(path: &[Segment]) -> TokenStream {
+    let mut output = quote! { self.__capture };
+    for segment in path {
+        output = match segment {
+            Segment::Field(ident) => quote! { #output.#ident },
+            Segment::Variant {
+                field_ident,
+                enum_ident,
+                variant_ident,
+                ..
+            } => quote! {
+                (match &mut #output.#field_ident {
+                    ::core::option::Option::Some(#enum_ident::#variant_ident(capture)) => capture,
+                    _ => ::core::unreachable!(),
+                })
+            },
+        };
+    }
+    output
+}
@@ -0,0 +1,403 @@
+use super::{pattern_string::class_ranges, Group, HitoriAttribute, Tree};
+use crate::{parse::repeat::Repeat, utils::expr_eval_lit_usize};
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+use std::collections::BTreeMap;
+use syn::Expr;
+
+/// Number of NFA states past which the automaton is rejected
+const MAX_NFA_STATES: usize = 4096;
+
+/// Number of DFA states past which the automaton is rejected
+const MAX_DFA_STATES: usize = 256;
+
+enum NfaState {
+    Class(Vec<(u32, u32)>, usize),
+    /// Epsilon transitions, from the highest priority to the lowest
+    Split(Vec<usize>),
+    Match,
+}
+
+struct Nfa<'a> {
+    states: Vec<NfaState>,
+    expr: &'a Expr,
+}
+
+impl Nfa<'_> {
+    fn push(&mut self, state: NfaState) -> syn::Result<usize> {
+        if self.states.len() == MAX_NFA_STATES {
+            return Err(syn::Error::new_spanned(
+                self.expr,
+                format!("automaton of this pattern has more than {MAX_NFA_STATES} NFA states"),
+            ));
+        }
+        self.states.push(state);
+        Ok(self.states.len() - 1)
+    }
+
+    /// Adds states matching `expr` followed by `next`, returning the first one
+    fn push_expr(&mut self, expr: &Expr, next: usize) -> syn::Result<usize> {
+        let (group, repeat) = match expr.try_into()? {
+            Tree::Test(test) => {
+                let class = class_ranges(test).ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        test,
+                        "`automaton` requires simple tests, which compare the character \
+                        with literals, check literal ranges or call `is_ascii_*` methods",
+                    )
+                })?;
+                return self.push(NfaState::Class(class, next));
+            }
+            Tree::Group(group, None) => (group, None),
+            Tree::Group(group, Some(HitoriAttribute::Repeat(repeat))) => (group, Some(repeat)),
+            Tree::Group(_, Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    "`automaton` cannot be used with attributes other than `repeat`",
+                ))
+            }
+        };
+        let lit = |bound: &Expr| {
+            expr_eval_lit_usize(bound).ok_or_else(|| {
+                syn::Error::new_spanned(bound, "`automaton` requires literal `repeat` bounds")
+            })
+        };
+        let (lo, hi) = match repeat {
+            None => return self.push_group(&group, next),
+            Some(Repeat::Exact(exact)) => {
+                let exact = lit(&exact)?;
+                (exact, Some(exact))
+            }
+            Some(Repeat::InInclusive {
+                lo_included,
+                hi_excluded,
+            }) => {
+                let lo = lit(&lo_included)?;
+                match hi_excluded {
+                    Some(hi_excluded) => {
+                        let hi = lit(&hi_excluded)?
+                            .checked_sub(1)
+                            .filter(|hi| lo <= *hi)
+                            .ok_or_else(|| {
+                                syn::Error::new_spanned(&hi_excluded, "`repeat` range is empty")
+                            })?;
+                        (lo, Some(hi))
+                    }
+                    None => (lo, None),
+                }
+            }
+        };
+        let mut first = match hi {
+            Some(hi) => {
+                let mut first = next;
+                for _ in lo..hi {
+                    let inner = self.push_group(&group, first)?;
+                    first = self.push(NfaState::Split(vec![inner, next]))?;
+                }
+                first
+            }
+            None => {
+                let split = self.push(NfaState::Split(vec![]))?;
+                let inner = self.push_group(&group, split)?;
+                self.states[split] = NfaState::Split(vec![inner, next]);
+                split
+            }
+        };
+        for _ in 0..lo {
+            first = self.push_group(&group, first)?;
+        }
+        Ok(first)
+    }
+
+    fn push_group(&mut self, group: &Group, next: usize) -> syn::Result<usize> {
+        match group {
+            Group::All(all) => {
+                let mut first = next;
+                for expr in all.iter().rev() {
+                    first = self.push_expr(expr, first)?;
+                }
+                Ok(first)
+            }
+            Group::Any(any) => {
+                let mut branches = vec![];
+                for expr in any.iter() {
+                    branches.push(self.push_expr(expr, next)?);
+                }
+                self.push(NfaState::Split(branches))
+            }
+        }
+    }
+
+    /// Adds `id` and states reachable from it by epsilon transitions
+    /// in priority order, returning `true` once a match is reached,
+    /// because every state after it has lower priority
+    fn add_closure(&self, id: usize, set: &mut Vec<usize>, is_visited: &mut [bool]) -> bool {
+        if std::mem::replace(&mut is_visited[id], true) {
+            return false;
+        }
+        match &self.states[id] {
+            NfaState::Class(..) => {
+                set.push(id);
+                false
+            }
+            NfaState::Split(nexts) => nexts
+                .iter()
+                .any(|next| self.add_closure(*next, set, is_visited)),
+            NfaState::Match => true,
+        }
+    }
+
+    fn closure(&self, ids: impl IntoIterator<Item = usize>) -> DfaKey {
+        let mut set = vec![];
+        let mut is_visited = vec![false; self.states.len()];
+        let is_match = ids
+            .into_iter()
+            .any(|id| self.add_closure(id, &mut set, &mut is_visited));
+        DfaKey { set, is_match }
+    }
+
+    /// Elementary intervals that every class either contains or excludes
+    fn alphabet(&self) -> Vec<(u32, u32)> {
+        let mut bounds = vec![];
+        for state in &self.states {
+            if let NfaState::Class(class, _) = state {
+                for (lo, hi) in class {
+                    bounds.push(*lo);
+                    bounds.push(*hi + 1);
+                }
+            }
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+        bounds
+            .windows(2)
+            .map(|window| (window[0], window[1] - 1))
+            .collect()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct DfaKey {
+    /// Class states in priority order
+    set: Vec<usize>,
+    is_match: bool,
+}
+
+struct DfaState {
+    is_match: bool,
+    /// Target states by elementary intervals
+    transitions: Vec<((u32, u32), usize)>,
+}
+
+/// Deterministic automaton of a pattern, which every test of is a simple test
+/// (see `pattern_string::class_items`)
+///
+/// Matches are leftmost-first like in [regex]: repetitions are greedy
+/// and earlier any-pattern branches are preferred, but unlike step-by-step
+/// matching, a repetition gives characters back when the rest of the pattern
+/// needs them.
+///
+/// [regex]: https://docs.rs/regex
+pub struct Dfa {
+    states: Vec<DfaState>,
+}
+
+impl Dfa {
+    /// Builds the automaton, failing if the pattern contains other tests,
+    /// attributes other than `repeat` with literal bounds, or is too large
+    pub fn new(expr: &Expr) -> syn::Result<Self> {
+        let mut nfa = Nfa {
+            states: vec![],
+            expr,
+        };
+        let nfa_match = nfa.push(NfaState::Match)?;
+        let nfa_start = nfa.push_expr(expr, nfa_match)?;
+        let alphabet = nfa.alphabet();
+
+        let mut ids = BTreeMap::new();
+        let mut keys = vec![nfa.closure([nfa_start])];
+        ids.insert(keys[0].clone(), 0);
+        let mut states = vec![];
+        while let Some(key) = keys.get(states.len()).cloned() {
+            let mut transitions = vec![];
+            for interval in &alphabet {
+                let next = nfa.closure(key.set.iter().filter_map(|id| {
+                    match &nfa.states[*id] {
+                        NfaState::Class(class, next)
+                            if class
+                                .iter()
+                                .any(|(lo, hi)| (*lo..=*hi).contains(&interval.0)) =>
+                        {
+                            Some(*next)
+                        }
+                        _ => None,
+                    }
+                }));
+                if next.set.is_empty() && !next.is_match {
+                    continue;
+                }
+                let next_id = match ids.get(&next) {
+                    Some(id) => *id,
+                    None if keys.len() < MAX_DFA_STATES => {
+                        ids.insert(next.clone(), keys.len());
+                        keys.push(next);
+                        keys.len() - 1
+                    }
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            expr,
+                            format!(
+                                "automaton of this pattern has more than \
+                                {MAX_DFA_STATES} DFA states"
+                            ),
+                        ))
+                    }
+                };
+                transitions.push((*interval, next_id));
+            }
+            states.push(DfaState {
+                is_match: key.is_match,
+                transitions,
+            });
+        }
+        Ok(Self { states })
+    }
+
+    /// Body of `starts_with`, running the automaton until it has no
+    /// transition for the next character and returning the last match
+    pub fn starts_with_block(&self, hitori_ident: &Ident) -> TokenStream {
+        let state_lit = Literal::usize_unsuffixed;
+        let mut arms = vec![];
+        for (id, state) in self.states.iter().enumerate() {
+            let mut patterns_by_target: BTreeMap<usize, Vec<TokenStream>> = BTreeMap::new();
+            for ((lo, hi), target) in &state.transitions {
+                patterns_by_target
+                    .entry(*target)
+                    .or_default()
+                    .extend(char_patterns(*lo, *hi));
+            }
+            let id = state_lit(id);
+            arms.extend(
+                patterns_by_target
+                    .into_iter()
+                    // intervals of surrogates only have no patterns
+                    .filter(|(_, patterns)| !patterns.is_empty())
+                    .map(|(target, patterns)| {
+                        let target = state_lit(target);
+                        quote! { (#id, #(#patterns)|*) => #target, }
+                    }),
+            );
+        }
+        let if_state_is_match = |is_final: bool, then: TokenStream| {
+            let ids: Vec<_> = self
+                .states
+                .iter()
+                .enumerate()
+                .filter(|(_, state)| state.is_match && state.transitions.is_empty() == is_final)
+                .map(|(id, _)| state_lit(id))
+                .collect();
+            (!ids.is_empty()).then(|| {
+                quote! {
+                    if ::core::matches!(state, #(#ids)|*) {
+                        #then
+                    }
+                }
+            })
+        };
+        let matched = |end: TokenStream, iter_remainder: TokenStream, is_advanced: TokenStream| {
+            quote! {
+                ::core::option::Option::Some(#hitori_ident::Match {
+                    range: start..#end,
+                    capture: ::core::default::Default::default(),
+                    iter_remainder: #iter_remainder,
+                    is_iter_advanced: !is_first || #is_advanced,
+                })
+            }
+        };
+        if self.states[0].transitions.is_empty() {
+            return if self.states[0].is_match {
+                let matched = matched(quote! { start }, quote! { iter }, quote! { false });
+                quote! {
+                    let iter = ::core::iter::IntoIterator::into_iter(iter);
+                    #matched
+                }
+            } else {
+                quote! {
+                    let _ = (start, is_first, iter);
+                    ::core::option::Option::None
+                }
+            };
+        }
+        let start_matched = if self.states[0].is_match {
+            quote! {
+                ::core::option::Option::Some((
+                    ::core::clone::Clone::clone(&start),
+                    ::core::clone::Clone::clone(&iter),
+                    false,
+                ))
+            }
+        } else {
+            quote! { ::core::option::Option::None }
+        };
+        let final_matched = matched(quote! { end }, quote! { iter }, quote! { true });
+        let if_final = if_state_is_match(true, quote! { return #final_matched; });
+        let if_match = if_state_is_match(
+            false,
+            quote! {
+                last_matched = ::core::option::Option::Some((
+                    end,
+                    ::core::clone::Clone::clone(&iter),
+                    true,
+                ));
+            },
+        );
+        let last_matched = matched(
+            quote! { end },
+            quote! { iter_remainder },
+            quote! { is_advanced },
+        );
+        quote! {
+            let mut iter = ::core::iter::IntoIterator::into_iter(iter);
+            let mut last_matched = #start_matched;
+            let mut state = 0usize;
+            while let ::core::option::Option::Some((end, ch)) =
+                ::core::iter::Iterator::next(&mut iter)
+            {
+                state = match (state, ch) {
+                    #(#arms)*
+                    _ => break,
+                };
+                #if_final
+                #if_match
+            }
+            match last_matched {
+                ::core::option::Option::Some((end, iter_remainder, is_advanced)) => #last_matched,
+                ::core::option::Option::None => ::core::option::Option::None,
+            }
+        }
+    }
+}
+
+/// Patterns of the valid characters in the inclusive range of codes
+fn char_patterns(lo: u32, hi: u32) -> Vec<TokenStream> {
+    const SURROGATES: (u32, u32) = (0xD800, 0xDFFF);
+    let mut ranges = vec![];
+    if lo < SURROGATES.0 {
+        ranges.push((lo, hi.min(SURROGATES.0 - 1)));
+    }
+    if hi > SURROGATES.1 {
+        ranges.push((lo.max(SURROGATES.1 + 1), hi));
+    }
+    ranges
+        .into_iter()
+        .filter_map(|(lo, hi)| {
+            let lo = Literal::character(char::from_u32(lo)?);
+            let hi = Literal::character(char::from_u32(hi)?);
+            Some(if lo.to_string() == hi.to_string() {
+                quote! { #lo }
+            } else {
+                quote! { #lo..=#hi }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+use super::{len, pattern_string, Group, HitoriAttribute, Tree};
+use crate::parse::position::Position;
+use syn::Expr;
+
+fn group_positions(group: &Group, output: &mut Vec<String>) -> syn::Result<()> {
+    match group {
+        Group::All(exprs) | Group::Any(exprs) => {
+            for expr in exprs.iter() {
+                positions(expr.try_into()?, output)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Descriptions of `position` attributes in the tree
+fn positions(tree: Tree, output: &mut Vec<String>) -> syn::Result<()> {
+    if let Tree::Group(group, attr) = tree {
+        if let Some(HitoriAttribute::Position(position)) = &attr {
+            let pattern = pattern_string::group_pattern_string(&group)?;
+            output.push(format!(
+                "`{pattern}` {}",
+                match position {
+                    Position::First => "only matches at the start of the input",
+                    Position::Last => "only matches at the end of the input",
+                    Position::FirstAndLast => "only matches the whole input",
+                }
+            ));
+        }
+        group_positions(&group, output)?;
+    }
+    Ok(())
+}
+
+/// Count of characters with the noun in the matching number
+fn characters(count: usize) -> String {
+    if count == 1 {
+        "1 character".into()
+    } else {
+        format!("{count} characters")
+    }
+}
+
+/// Documentation of the generated implementation, which describes
+/// the pattern, its match length and position assertions
+pub fn impl_doc(expr: &Expr) -> syn::Result<String> {
+    let mut output = format!("Pattern: `{}`", pattern_string::pattern_string(expr)?);
+    output.push_str(&match len::bounds(expr.try_into()?)? {
+        (min, Some(max)) if min == max => format!("\n\nMatches exactly {}", characters(min)),
+        (min, Some(max)) => format!("\n\nMatches from {min} to {}", characters(max)),
+        (min, None) => format!("\n\nMatches at least {}", characters(min)),
+    });
+    let mut position_docs = vec![];
+    positions(expr.try_into()?, &mut position_docs)?;
+    if !position_docs.is_empty() {
+        output.push_str("\n\nPosition assertions:\n");
+        for doc in position_docs {
+            output.push_str("\n- ");
+            output.push_str(&doc);
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,54 @@
+use super::{Group, HitoriAttribute, Tree};
+
+/// Minimum and maximum (if bounded) numbers of characters consumed by a match
+pub type Bounds = (usize, Option<usize>);
+
+fn group_bounds(group: &Group) -> syn::Result<Bounds> {
+    match group {
+        Group::All(all) => {
+            let mut output: Bounds = (0, Some(0));
+            for expr in all.iter() {
+                let (min, max) = bounds(expr.try_into()?)?;
+                output.0 = output.0.saturating_add(min);
+                output.1 = output
+                    .1
+                    .zip(max)
+                    .and_then(|(lhs, rhs)| lhs.checked_add(rhs));
+            }
+            Ok(output)
+        }
+        Group::Any(any) => {
+            let mut iter = any.iter();
+            let mut output = match iter.next() {
+                Some(first) => bounds(first.try_into()?)?,
+                None => return Ok((0, Some(0))),
+            };
+            for expr in iter {
+                let (min, max) = bounds(expr.try_into()?)?;
+                output.0 = output.0.min(min);
+                output.1 = output.1.zip(max).map(|(lhs, rhs)| lhs.max(rhs));
+            }
+            Ok(output)
+        }
+    }
+}
+
+/// Bounds of the number of characters the tree consumes, using literal
+/// repetition bounds (non-literal ones are treated as unknown)
+pub fn bounds(tree: Tree) -> syn::Result<Bounds> {
+    match tree {
+        Tree::Group(group, Some(HitoriAttribute::Repeat(repeat))) => {
+            let (min, max) = group_bounds(&group)?;
+            Ok((
+                repeat.lo_lit().unwrap_or_default().saturating_mul(min),
+                match (max, repeat.hi_lit()) {
+                    (Some(0), _) => Some(0),
+                    (Some(max), Some(hi)) => max.checked_mul(hi),
+                    _ => None,
+                },
+            ))
+        }
+        Tree::Group(group, _) => group_bounds(&group),
+        Tree::Test(_) => Ok((1, Some(1))),
+    }
+}
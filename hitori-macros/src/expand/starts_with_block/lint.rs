@@ -0,0 +1,290 @@
+use super::{pattern_string::class_ranges, Group, HitoriAttribute, Tree};
+use crate::parse::repeat::Repeat;
+use quote::ToTokens as _;
+use syn::{Expr, ExprClosure, Pat, PatIdent, PatType};
+
+/// Characters accepted by a test
+enum CharClass {
+    /// Simple test (see `pattern_string::class_items`), represented with sorted
+    /// non-overlapping inclusive ranges of codes
+    Ranges(Vec<(u32, u32)>),
+    /// Any other test, which is only known to accept the same characters
+    /// as a syntactically identical one
+    Opaque(String),
+}
+
+impl CharClass {
+    fn ranges(mut ranges: Vec<(u32, u32)>) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(u32, u32)> = vec![];
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= last_hi.saturating_add(1) => {
+                    *last_hi = (*last_hi).max(hi);
+                }
+                _ => merged.push((lo, hi)),
+            }
+        }
+        Self::Ranges(merged)
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Ranges(lhs), Self::Ranges(rhs)) => lhs.iter().all(|(lo, hi)| {
+                rhs.iter()
+                    .any(|(rhs_lo, rhs_hi)| rhs_lo <= lo && hi <= rhs_hi)
+            }),
+            (Self::Opaque(lhs), Self::Opaque(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+/// Whether every class of `lhs` is a subset of some class of `rhs`
+fn is_subset(lhs: &[CharClass], rhs: &[CharClass]) -> bool {
+    lhs.iter()
+        .all(|lhs| rhs.iter().any(|rhs| lhs.is_subset(rhs)))
+}
+
+pub fn closure_param(closure: &ExprClosure) -> Option<&PatIdent> {
+    if closure.inputs.len() != 1 {
+        return None;
+    }
+    match &closure.inputs[0] {
+        Pat::Ident(ident) => Some(ident),
+        Pat::Type(PatType { pat, .. }) => match pat.as_ref() {
+            Pat::Ident(ident) => Some(ident),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Mode {
+    is_mut: bool,
+    is_test_with_index: bool,
+    is_test_by_ref: bool,
+}
+
+impl Mode {
+    fn with_attr(mut self, attr: Option<&HitoriAttribute>) -> Self {
+        match attr {
+            Some(HitoriAttribute::WithIndex) => self.is_test_with_index = true,
+            Some(HitoriAttribute::ByRef) => self.is_test_by_ref = true,
+            _ => (),
+        }
+        self
+    }
+
+    fn test_class(self, test: &Expr) -> Option<CharClass> {
+        if !self.is_test_with_index && !self.is_test_by_ref {
+            if let Some(ranges) = class_ranges(test) {
+                return Some(CharClass::ranges(ranges));
+            }
+        }
+        // Tests of mutable expressions could depend on the state
+        (!self.is_mut).then(|| {
+            CharClass::Opaque(format!(
+                "{} {} {}",
+                self.is_test_with_index,
+                self.is_test_by_ref,
+                test.to_token_stream()
+            ))
+        })
+    }
+
+    fn group_first_classes(self, group: &Group) -> syn::Result<Option<Vec<CharClass>>> {
+        Ok(match group {
+            Group::All(all) => match all.first() {
+                Some(first) => self.first_classes(first.try_into()?)?,
+                None => None,
+            },
+            Group::Any(any) => {
+                let mut output = vec![];
+                for expr in any.iter() {
+                    match self.first_classes(expr.try_into()?)? {
+                        Some(mut classes) => output.append(&mut classes),
+                        None => return Ok(None),
+                    }
+                }
+                (!output.is_empty()).then_some(output)
+            }
+        })
+    }
+
+    /// Classes of characters the tree could start matching with,
+    /// if it always consumes at least one character
+    fn first_classes(self, tree: Tree) -> syn::Result<Option<Vec<CharClass>>> {
+        match tree {
+            Tree::Group(group, attr) => match &attr {
+                Some(HitoriAttribute::Repeat(repeat)) => {
+                    if repeat.lo_lit().map_or(false, |lo| lo > 0) {
+                        self.group_first_classes(&group)
+                    } else {
+                        Ok(None)
+                    }
+                }
+                _ => self.with_attr(attr.as_ref()).group_first_classes(&group),
+            },
+            Tree::Test(test) => Ok(self.test_class(test).map(|class| vec![class])),
+        }
+    }
+
+    fn group_single_char_classes(self, group: &Group) -> syn::Result<Option<Vec<CharClass>>> {
+        Ok(match group {
+            Group::All(all) if all.len() == 1 => self.single_char_classes((&all[0]).try_into()?)?,
+            Group::All(_) => None,
+            Group::Any(any) => {
+                let mut output = vec![];
+                for expr in any.iter() {
+                    match self.single_char_classes(expr.try_into()?)? {
+                        Some(mut classes) => output.append(&mut classes),
+                        None => return Ok(None),
+                    }
+                }
+                (!output.is_empty()).then_some(output)
+            }
+        })
+    }
+
+    /// Classes of characters the tree matches, if it always matches
+    /// exactly one character that passes its test
+    fn single_char_classes(self, tree: Tree) -> syn::Result<Option<Vec<CharClass>>> {
+        match tree {
+            Tree::Group(group, attr) => match &attr {
+                None
+                | Some(
+                    HitoriAttribute::Capture(_)
+                    | HitoriAttribute::OnMatch(_)
+                    | HitoriAttribute::WithIndex
+                    | HitoriAttribute::ByRef,
+                ) => self
+                    .with_attr(attr.as_ref())
+                    .group_single_char_classes(&group),
+                _ => Ok(None),
+            },
+            Tree::Test(test) => Ok(self.test_class(test).map(|class| vec![class])),
+        }
+    }
+
+    fn group_trailing_classes(self, group: &Group) -> syn::Result<Option<Vec<CharClass>>> {
+        match group {
+            Group::All(all) | Group::Any(all) if all.len() == 1 => {
+                self.trailing_classes((&all[0]).try_into()?)
+            }
+            Group::All(all) => match all.last() {
+                Some(last) => self.trailing_classes(last.try_into()?),
+                None => Ok(None),
+            },
+            Group::Any(_) => Ok(None),
+        }
+    }
+
+    /// Classes of characters consumed by an unbounded repetition
+    /// the tree ends with
+    fn trailing_classes(self, tree: Tree) -> syn::Result<Option<Vec<CharClass>>> {
+        match tree {
+            Tree::Group(
+                group,
+                Some(HitoriAttribute::Repeat(Repeat::InInclusive {
+                    hi_excluded: None, ..
+                })),
+            ) => self.group_single_char_classes(&group),
+            Tree::Group(group, attr) => match &attr {
+                None
+                | Some(
+                    HitoriAttribute::Capture(_)
+                    | HitoriAttribute::CaptureStruct(_)
+                    | HitoriAttribute::Count(_)
+                    | HitoriAttribute::OnMatch(_)
+                    | HitoriAttribute::WithIndex
+                    | HitoriAttribute::ByRef,
+                ) => self.with_attr(attr.as_ref()).group_trailing_classes(&group),
+                _ => Ok(None),
+            },
+            Tree::Test(_) => Ok(None),
+        }
+    }
+
+    fn check_group(self, group: &Group, errors: &mut Vec<syn::Error>) -> syn::Result<()> {
+        match group {
+            Group::All(all) => {
+                for (prev, next) in all.iter().zip(all.iter().skip(1)) {
+                    if let (Some(swallowed), Some(first)) = (
+                        self.trailing_classes(prev.try_into()?)?,
+                        self.first_classes(next.try_into()?)?,
+                    ) {
+                        if is_subset(&first, &swallowed) {
+                            errors.push(syn::Error::new_spanned(
+                                next,
+                                "this never matches, because the preceding repetition \
+                                consumes every character it could start with \
+                                (repetitions do not give characters back)",
+                            ));
+                        }
+                    }
+                }
+            }
+            Group::Any(any) => {
+                for (index, branch) in any.iter().enumerate() {
+                    let first = match self.first_classes(branch.try_into()?)? {
+                        Some(first) => first,
+                        None => continue,
+                    };
+                    for earlier in any.iter().take(index) {
+                        let is_shadowed = (!self.is_mut
+                            && earlier.to_token_stream().to_string()
+                                == branch.to_token_stream().to_string())
+                            || self
+                                .single_char_classes(earlier.try_into()?)?
+                                .map_or(false, |earlier| is_subset(&first, &earlier));
+                        if is_shadowed {
+                            errors.push(syn::Error::new_spanned(
+                                branch,
+                                "this branch is never chosen, \
+                                because an earlier branch always matches first",
+                            ));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        match group {
+            Group::All(exprs) | Group::Any(exprs) => {
+                for expr in exprs.iter() {
+                    self.check(expr.try_into()?, errors)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check(self, tree: Tree, errors: &mut Vec<syn::Error>) -> syn::Result<()> {
+        match tree {
+            Tree::Group(group, attr) => self.with_attr(attr.as_ref()).check_group(&group, errors),
+            Tree::Test(_) => Ok(()),
+        }
+    }
+}
+
+/// Finds parts of the pattern that can never match, because of repetitions
+/// consuming the following element or branches shadowed by earlier ones
+pub fn check(tree: Tree, is_mut: bool, is_test_by_ref: bool) -> syn::Result<()> {
+    let mut errors = vec![];
+    Mode {
+        is_mut,
+        is_test_with_index: false,
+        is_test_by_ref,
+    }
+    .check(tree, &mut errors)?;
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        Some(mut error) => {
+            error.extend(errors);
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,118 @@
+use super::{
+    len,
+    pattern_string::{class_items, ClassItem},
+    Group, HitoriAttribute, Tree,
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Expr, Lit};
+
+/// Element of the sequence every match consists of
+enum Element {
+    /// Test comparing the character with a single [`char`] literal
+    Char(char),
+    /// Any other part of the pattern, with its maximum length if bounded
+    Other(Option<usize>),
+}
+
+/// Character of a test like `|ch| ch == 'a'`
+fn test_char(test: &Expr) -> Option<char> {
+    match class_items(test)?.as_slice() {
+        [ClassItem::Char(
+            ch,
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Char(_), ..
+            }),
+        )] => Some(*ch),
+        _ => None,
+    }
+}
+
+/// Flattens groups that always match exactly once into the sequence
+fn extend_elements(tree: Tree, output: &mut Vec<Element>) -> syn::Result<()> {
+    match tree {
+        Tree::Group(
+            Group::All(exprs),
+            None
+            | Some(
+                HitoriAttribute::Capture(_)
+                | HitoriAttribute::CaptureStruct(_)
+                | HitoriAttribute::Position(_)
+                | HitoriAttribute::OnMatch(_),
+            ),
+        ) => {
+            for expr in exprs.iter() {
+                extend_elements(expr.try_into()?, output)?;
+            }
+        }
+        Tree::Group(Group::Any(exprs), None) if exprs.len() == 1 => {
+            extend_elements((&exprs[0]).try_into()?, output)?;
+        }
+        Tree::Test(test) if test_char(test).is_some() => {
+            output.extend(test_char(test).map(Element::Char));
+        }
+        tree => output.push(Element::Other(len::bounds(tree)?.1)),
+    }
+    Ok(())
+}
+
+/// Implementation of `literal` method, if every match contains characters
+/// in a row that are tested with single-literal comparisons. Literals with
+/// bounded offsets are preferred, and then the longer ones.
+pub fn literal_fn(
+    hitori_ident: &Ident,
+    expr: &Expr,
+    is_test_by_ref: bool,
+) -> syn::Result<Option<TokenStream>> {
+    if is_test_by_ref {
+        return Ok(None);
+    }
+    let mut elements = vec![];
+    extend_elements(expr.try_into()?, &mut elements)?;
+
+    let mut best: Option<(String, Option<usize>)> = None;
+    let mut offset = Some(0);
+    let mut text = String::new();
+    let mut text_offset = Some(0);
+    for element in elements.iter().map(Some).chain([None]) {
+        if let Some(Element::Char(ch)) = element {
+            if text.is_empty() {
+                text_offset = offset;
+            }
+            text.push(*ch);
+            offset = offset.map(|offset| offset + 1);
+            continue;
+        }
+        if !text.is_empty() {
+            let key = |(text, offset): &(String, Option<usize>)| (offset.is_some(), text.len());
+            let candidate = (std::mem::take(&mut text), text_offset);
+            if best
+                .as_ref()
+                .map_or(true, |best| key(&candidate) > key(best))
+            {
+                best = Some(candidate);
+            }
+        }
+        if let Some(Element::Other(max_len)) = element {
+            offset = offset
+                .zip(*max_len)
+                .map(|(offset, max_len)| offset + max_len);
+        }
+    }
+
+    Ok(best.map(|(text, max_offset)| {
+        let max_offset = max_offset.map_or_else(
+            || quote! { ::core::option::Option::None },
+            |max_offset| quote! { ::core::option::Option::Some(#max_offset) },
+        );
+        quote! {
+            #[inline]
+            fn literal(&self) -> ::core::option::Option<#hitori_ident::Literal> {
+                ::core::option::Option::Some(#hitori_ident::Literal {
+                    text: #text,
+                    max_offset: #max_offset,
+                })
+            }
+        }
+    }))
+}
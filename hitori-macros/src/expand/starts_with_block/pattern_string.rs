@@ -0,0 +1,321 @@
+use super::{lint::closure_param, Group, HitoriAttribute, Tree};
+use crate::{
+    parse::{position::Position, repeat::Repeat},
+    utils::expr_eval_lit_usize,
+};
+use proc_macro2::Ident;
+use quote::ToTokens as _;
+use syn::{BinOp, Expr, ExprBinary, ExprRange, Lit, PatIdent, RangeLimits, Stmt};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Atom,
+    Concat,
+    Alt,
+}
+
+struct Rendered {
+    text: String,
+    precedence: Precedence,
+}
+
+impl Rendered {
+    fn new(text: String, precedence: Precedence) -> Self {
+        Self { text, precedence }
+    }
+
+    fn with_max_precedence(self, max: Precedence) -> String {
+        if self.precedence > max {
+            format!("(?:{})", self.text)
+        } else {
+            self.text
+        }
+    }
+}
+
+fn escape(ch: char, is_in_class: bool) -> String {
+    let is_meta = if is_in_class {
+        matches!(ch, '\\' | '[' | ']' | '^' | '-')
+    } else {
+        matches!(
+            ch,
+            '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$'
+        )
+    };
+    if is_meta {
+        format!("\\{ch}")
+    } else if ch.is_control() {
+        format!("\\x{{{:X}}}", u32::from(ch))
+    } else {
+        ch.to_string()
+    }
+}
+
+/// Item of a character class along with the syntax it comes from
+pub enum ClassItem<'a> {
+    /// Character the test compares with (e.g. `'a'` of `ch == 'a'`)
+    Char(char, &'a Expr),
+    /// Inclusive range the test checks (e.g. `('a'..='z')` of `('a'..='z').contains(&ch)`)
+    Range(char, char, &'a Expr),
+    /// `is_ascii_*` method the test calls
+    Ranges(&'static str, &'a Ident),
+}
+
+fn lit_char(expr: &Expr) -> Option<char> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Char(ch) => Some(ch.value()),
+            Lit::Byte(byte) => Some(char::from(byte.value())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Inclusive bounds of a range like `'a'..='z'`
+fn lit_range(expr: &Expr) -> Option<(char, char)> {
+    match expr {
+        Expr::Paren(paren) => lit_range(&paren.expr),
+        Expr::Range(ExprRange {
+            start: Some(from),
+            limits: RangeLimits::Closed(_),
+            end: Some(to),
+            ..
+        }) => Some((lit_char(from)?, lit_char(to)?)),
+        _ => None,
+    }
+}
+
+fn ascii_ranges(method: &str) -> Option<&'static str> {
+    Some(match method {
+        "is_ascii_digit" => "0-9",
+        "is_ascii_hexdigit" => "0-9a-fA-F",
+        "is_ascii_lowercase" => "a-z",
+        "is_ascii_uppercase" => "A-Z",
+        "is_ascii_alphabetic" => "a-zA-Z",
+        "is_ascii_alphanumeric" => "a-zA-Z0-9",
+        _ => return None,
+    })
+}
+
+fn extend_class_items<'a>(
+    body: &'a Expr,
+    param: &PatIdent,
+    output: &mut Vec<ClassItem<'a>>,
+) -> bool {
+    let is_param =
+        |expr: &Expr| matches!(expr, Expr::Path(path) if path.path.is_ident(&param.ident));
+    match body {
+        Expr::Paren(paren) => extend_class_items(&paren.expr, param, output),
+        Expr::Block(block) => match block.block.stmts.as_slice() {
+            [Stmt::Expr(expr, None)] => extend_class_items(expr, param, output),
+            _ => false,
+        },
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Or(_),
+            right,
+            ..
+        }) => extend_class_items(left, param, output) && extend_class_items(right, param, output),
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Eq(_),
+            right,
+            ..
+        }) => match (lit_char(left), lit_char(right)) {
+            (Some(ch), None) if is_param(right) => {
+                output.push(ClassItem::Char(ch, left));
+                true
+            }
+            (None, Some(ch)) if is_param(left) => {
+                output.push(ClassItem::Char(ch, right));
+                true
+            }
+            _ => false,
+        },
+        Expr::MethodCall(call) if call.args.is_empty() && is_param(&call.receiver) => {
+            match ascii_ranges(&call.method.to_string()) {
+                Some(ranges) => {
+                    output.push(ClassItem::Ranges(ranges, &call.method));
+                    true
+                }
+                None => false,
+            }
+        }
+        // `('a'..='z').contains(&ch)`
+        Expr::MethodCall(call) if call.method == "contains" && call.args.len() == 1 => {
+            match (lit_range(&call.receiver), &call.args[0]) {
+                (Some((lo, hi)), Expr::Reference(arg)) if is_param(&arg.expr) => {
+                    output.push(ClassItem::Range(lo, hi, &call.receiver));
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Characters accepted by a simple test (see hitori crate docs)
+/// like `|ch| ch == '_' || ch.is_ascii_digit()`, or `None` for other tests
+pub fn class_items(test: &Expr) -> Option<Vec<ClassItem<'_>>> {
+    let closure = match test {
+        Expr::Closure(closure) => closure,
+        _ => return None,
+    };
+    let param = closure_param(closure)?;
+    let mut items = vec![];
+    extend_class_items(&closure.body, param, &mut items).then_some(items)
+}
+
+/// Inclusive ranges of character codes accepted by a test,
+/// without reversed ones
+pub fn class_ranges(test: &Expr) -> Option<Vec<(u32, u32)>> {
+    Some(
+        class_items(test)?
+            .into_iter()
+            .flat_map(|item| match item {
+                ClassItem::Char(ch, _) => vec![(u32::from(ch), u32::from(ch))],
+                ClassItem::Range(lo, hi, _) => vec![(u32::from(lo), u32::from(hi))],
+                ClassItem::Ranges(ranges, _) => ranges
+                    .as_bytes()
+                    .chunks(3)
+                    .map(|range| (u32::from(range[0]), u32::from(range[2])))
+                    .collect(),
+            })
+            .filter(|(lo, hi)| lo <= hi)
+            .collect(),
+    )
+}
+
+fn test(test: &Expr) -> Rendered {
+    let text = match class_items(test).as_deref() {
+        Some([ClassItem::Char(ch, _)]) => escape(*ch, false),
+        Some(items) => {
+            let items: String = items
+                .iter()
+                .map(|item| match item {
+                    ClassItem::Char(ch, _) => escape(*ch, true),
+                    ClassItem::Range(lo, hi, _) => {
+                        format!("{}-{}", escape(*lo, true), escape(*hi, true))
+                    }
+                    ClassItem::Ranges(ranges, _) => (*ranges).into(),
+                })
+                .collect();
+            format!("[{items}]")
+        }
+        None => "<test>".into(),
+    };
+    Rendered::new(text, Precedence::Atom)
+}
+
+fn bound(expr: &Expr) -> String {
+    expr_eval_lit_usize(expr)
+        .map_or_else(|| expr.to_token_stream().to_string(), |lit| lit.to_string())
+}
+
+fn hi_included(hi_excluded: &Expr) -> String {
+    match (expr_eval_lit_usize(hi_excluded), hi_excluded) {
+        (Some(lit), _) => lit.saturating_sub(1).to_string(),
+        // `le = x` bounds are stored as `x + 1`
+        (
+            None,
+            Expr::Binary(ExprBinary {
+                left,
+                op: BinOp::Add(_),
+                right,
+                ..
+            }),
+        ) if expr_eval_lit_usize(right) == Some(1) => left.to_token_stream().to_string(),
+        (None, _) => format!("{} - 1", hi_excluded.to_token_stream()),
+    }
+}
+
+pub fn quantifier(repeat: &Repeat) -> String {
+    match repeat {
+        Repeat::Exact(exact) => format!("{{{}}}", bound(exact)),
+        Repeat::InInclusive {
+            lo_included,
+            hi_excluded,
+        } => {
+            let lo = bound(lo_included);
+            match hi_excluded.as_ref().map(hi_included) {
+                None if lo == "0" => "*".into(),
+                None if lo == "1" => "+".into(),
+                None => format!("{{{lo},}}"),
+                Some(hi) if lo == "0" && hi == "1" => "?".into(),
+                Some(hi) if lo == hi => format!("{{{lo}}}"),
+                Some(hi) => format!("{{{lo},{hi}}}"),
+            }
+        }
+    }
+}
+
+fn group(group: &Group) -> syn::Result<Rendered> {
+    let (exprs, separator, precedence) = match group {
+        Group::All(all) => (all, "", Precedence::Concat),
+        Group::Any(any) => (any, "|", Precedence::Alt),
+    };
+    if exprs.len() == 1 {
+        return tree((&exprs[0]).try_into()?);
+    }
+    let max_precedence = match group {
+        Group::All(_) => Precedence::Concat,
+        Group::Any(_) => Precedence::Alt,
+    };
+    let mut texts = vec![];
+    for expr in exprs.iter() {
+        let rendered = tree(expr.try_into()?)?;
+        texts.push(match max_precedence {
+            Precedence::Concat => rendered.with_max_precedence(Precedence::Concat),
+            _ => rendered.text,
+        });
+    }
+    Ok(Rendered::new(texts.join(separator), precedence))
+}
+
+fn tree(tree: Tree) -> syn::Result<Rendered> {
+    let (inner, attr) = match tree {
+        Tree::Group(inner, attr) => (group(&inner)?, attr),
+        Tree::Test(expr) => return Ok(test(expr)),
+    };
+    Ok(match attr {
+        Some(HitoriAttribute::Repeat(repeat)) => Rendered::new(
+            inner.with_max_precedence(Precedence::Atom) + &quantifier(&repeat),
+            Precedence::Concat,
+        ),
+        Some(HitoriAttribute::Capture(_)) => {
+            Rendered::new(format!("({})", inner.text), Precedence::Atom)
+        }
+        Some(HitoriAttribute::Position(position)) => {
+            let mut text = inner.with_max_precedence(Precedence::Concat);
+            if matches!(position, Position::First | Position::FirstAndLast) {
+                text.insert(0, '^');
+            }
+            if matches!(position, Position::Last | Position::FirstAndLast) {
+                text.push('$');
+            }
+            Rendered::new(text, Precedence::Concat)
+        }
+        Some(
+            HitoriAttribute::CaptureStruct(_)
+            | HitoriAttribute::Variants(_)
+            | HitoriAttribute::Count(_)
+            | HitoriAttribute::WithIndex
+            | HitoriAttribute::ByRef
+            | HitoriAttribute::OnMatch(_),
+        )
+        | None => inner,
+    })
+}
+
+/// Regex-like rendering of the pattern, where simple tests (see [`class_items`])
+/// are shown as characters or classes, and other tests as `<test>`
+pub fn pattern_string(expr: &Expr) -> syn::Result<String> {
+    tree(expr.try_into()?).map(|rendered| rendered.text)
+}
+
+/// *See [`pattern_string`]*
+pub fn group_pattern_string(group: &Group) -> syn::Result<String> {
+    self::group(group).map(|rendered| rendered.text)
+}
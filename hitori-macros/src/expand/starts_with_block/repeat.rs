@@ -30,8 +30,13 @@ fn bounds_decl(repeat: &Repeat) -> TokenStream {
     }
 }
 
-fn lo_test(inner_matches_ident: &Ident, inner_capture_idents: &BTreeSet<Ident>) -> TokenStream {
-    let capture = cache::Capture::new(inner_capture_idents);
+fn lo_test(
+    inner_matches_ident: &Ident,
+    capture_place: &TokenStream,
+    inner_capture_idents: &BTreeSet<Ident>,
+    capture_sink: Option<&Ident>,
+) -> TokenStream {
+    let capture = cache::Capture::new(capture_place, inner_capture_idents, capture_sink);
     let capture_cache = capture.cache();
     let capture_restore = capture.restore();
     quote! {
@@ -45,14 +50,28 @@ fn lo_test(inner_matches_ident: &Ident, inner_capture_idents: &BTreeSet<Ident>)
     }
 }
 
-fn vars_cache_update_restore(inner_capture_idents: &BTreeSet<Ident>) -> [TokenStream; 3] {
-    let vars = cache::Vars::unique_in(inner_capture_idents);
+fn vars_cache_update_restore(
+    inner_capture_idents: &BTreeSet<Ident>,
+    target: Option<&cache::Target>,
+) -> [TokenStream; 3] {
+    let vars = cache::Vars::unique_in(inner_capture_idents, target);
     [vars.cache(), vars.update(), vars.restore()]
 }
 
+fn count_set_increment(
+    capture_place: &TokenStream,
+    count_ident: Option<&Ident>,
+) -> [Option<TokenStream>; 2] {
+    [
+        count_ident.map(|ident| quote! { #capture_place.#ident = lo; }),
+        count_ident.map(|ident| quote! { #capture_place.#ident += 1; }),
+    ]
+}
+
 fn some_hi_test(
     inner_matches_ident: &Ident,
     [vars_cache, vars_update, vars_restore]: &[TokenStream; 3],
+    count_increment: Option<&TokenStream>,
 ) -> TokenStream {
     quote! {
         if lo + 1 == hi {
@@ -62,12 +81,15 @@ fn some_hi_test(
         for _ in lo + 2..hi {
             if self.#inner_matches_ident() {
                 #vars_update
+                #count_increment
             } else {
                 #vars_restore
                 return true;
             }
         }
-        if !self.#inner_matches_ident() {
+        if self.#inner_matches_ident() {
+            #count_increment
+        } else {
             #vars_restore
         }
     }
@@ -76,11 +98,13 @@ fn some_hi_test(
 fn none_hi_test(
     inner_matches_ident: &Ident,
     [vars_cache, vars_update, vars_restore]: &[TokenStream; 3],
+    count_increment: Option<&TokenStream>,
 ) -> TokenStream {
     quote! {
         #vars_cache
         while self.#inner_matches_ident() {
             #vars_update
+            #count_increment
         }
         #vars_restore
     }
@@ -89,16 +113,27 @@ fn none_hi_test(
 pub fn expand_block(
     repeat: &Repeat,
     inner_matches_ident: &Ident,
+    capture_place: &TokenStream,
     inner_capture_idents: &BTreeSet<Ident>,
+    capture_sink: Option<&Ident>,
+    count_ident: Option<&Ident>,
+    target: Option<&cache::Target>,
 ) -> TokenStream {
+    let [count_set, count_increment] = count_set_increment(capture_place, count_ident);
     let mut output = bounds_decl(repeat);
-    output.extend(lo_test(inner_matches_ident, inner_capture_idents));
+    output.extend(lo_test(
+        inner_matches_ident,
+        capture_place,
+        inner_capture_idents,
+        capture_sink,
+    ));
+    output.extend(count_set);
     if let Repeat::InInclusive { hi_excluded, .. } = repeat {
-        let vars_streams = vars_cache_update_restore(inner_capture_idents);
+        let vars_streams = vars_cache_update_restore(inner_capture_idents, target);
         output.extend(if hi_excluded.is_some() {
-            some_hi_test(inner_matches_ident, &vars_streams)
+            some_hi_test(inner_matches_ident, &vars_streams, count_increment.as_ref())
         } else {
-            none_hi_test(inner_matches_ident, &vars_streams)
+            none_hi_test(inner_matches_ident, &vars_streams, count_increment.as_ref())
         });
     }
     output.extend(quote! { true });
@@ -0,0 +1,63 @@
+use super::{Group, HitoriAttribute, Tree};
+use proc_macro2::Ident;
+use std::collections::BTreeSet;
+
+/// Capture fields of the current capture struct that are always set
+/// if the group matches
+pub fn group_guaranteed_capture_idents(group: &Group) -> syn::Result<BTreeSet<Ident>> {
+    match group {
+        Group::All(all) => {
+            let mut output = BTreeSet::new();
+            for expr in all.iter() {
+                output.append(&mut guaranteed_capture_idents(expr.try_into()?)?);
+            }
+            Ok(output)
+        }
+        Group::Any(any) => {
+            let mut iter = any.iter();
+            let mut output = match iter.next() {
+                Some(first) => guaranteed_capture_idents(first.try_into()?)?,
+                None => return Ok(BTreeSet::new()),
+            };
+            for expr in iter {
+                let branch = guaranteed_capture_idents(expr.try_into()?)?;
+                output.retain(|ident| branch.contains(ident));
+            }
+            Ok(output)
+        }
+    }
+}
+
+/// Capture fields of the current capture struct that are always set
+/// if the tree matches
+pub fn guaranteed_capture_idents(tree: Tree) -> syn::Result<BTreeSet<Ident>> {
+    Ok(match tree {
+        Tree::Group(group, attr) => match attr {
+            Some(HitoriAttribute::Capture(capture)) => {
+                let mut output = group_guaranteed_capture_idents(&group)?;
+                output.extend(capture.idents);
+                output
+            }
+            Some(HitoriAttribute::Repeat(repeat)) => {
+                if repeat.lo_lit().map_or(false, |lo| lo > 0) {
+                    group_guaranteed_capture_idents(&group)?
+                } else {
+                    BTreeSet::new()
+                }
+            }
+            // The nested struct counts as set, so that its own `required` fields
+            // could be checked against whether it always matches
+            Some(HitoriAttribute::CaptureStruct(ident)) => BTreeSet::from([ident]),
+            Some(HitoriAttribute::Variants(_)) => BTreeSet::new(),
+            Some(
+                HitoriAttribute::Count(_)
+                | HitoriAttribute::Position(_)
+                | HitoriAttribute::WithIndex
+                | HitoriAttribute::ByRef
+                | HitoriAttribute::OnMatch(_),
+            )
+            | None => group_guaranteed_capture_idents(&group)?,
+        },
+        Tree::Test(_) => BTreeSet::new(),
+    })
+}
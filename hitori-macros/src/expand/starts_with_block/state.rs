@@ -1,18 +1,88 @@
-use super::{cache, repeat, Group, HitoriAttribute, Tree};
-use crate::parse::{position::Position, repeat::Repeat};
+use super::{
+    cache,
+    capture_fields::{self, CaptureFields, Field, Segment},
+    pattern_string::{self, group_pattern_string, quantifier},
+    repeat, required, Group, HitoriAttribute, Tree,
+};
+use crate::parse::{
+    capture::{Capture, Keep},
+    position::Position,
+    repeat::Repeat,
+    variants::Variants,
+};
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote};
-use std::collections::BTreeSet;
+use quote::{format_ident, quote, ToTokens as _};
+use std::{collections::BTreeSet, fmt, mem};
 use syn::{punctuated::Punctuated, Expr, Token};
 
-#[derive(Default)]
 pub struct State {
     pub impl_wrapper_block: TokenStream,
     pub prev_subexpr_matches_ident: Option<Ident>,
+    pub capture_fields: CaptureFields,
+    capture_sink: Option<Ident>,
+    /// Whether captured ranges are stored as `(start, end)` pairs
+    is_capture_pairs: bool,
+    capture_path: Vec<Segment>,
+    /// Repetitions and any-pattern branches the current group is inside of,
+    /// relative to the current capture struct
+    doc_context: Vec<DocContext>,
+    required_checks: Vec<(Vec<Segment>, BTreeSet<Ident>)>,
     next_subexpr_index: usize,
+    is_mut: bool,
+    target: Option<cache::Target>,
+    has_ctx: bool,
+    is_try: bool,
+    is_test_with_index: bool,
+    is_test_by_ref: bool,
+    /// Whether capturing attributes are treated as plain groups
+    is_capture_free: bool,
+    /// `on_match` actions queued by their index, if they are deferred
+    deferred_actions: Vec<TokenStream>,
 }
 
 impl State {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        capture_ident: Ident,
+        capture_sink: Option<Ident>,
+        is_capture_pairs: bool,
+        is_mut: bool,
+        target: Option<cache::Target>,
+        has_ctx: bool,
+        is_try: bool,
+        is_test_by_ref: bool,
+    ) -> Self {
+        Self {
+            impl_wrapper_block: TokenStream::new(),
+            prev_subexpr_matches_ident: None,
+            capture_fields: CaptureFields::new(capture_ident, String::new()),
+            capture_sink,
+            is_capture_pairs,
+            capture_path: Vec::new(),
+            doc_context: Vec::new(),
+            required_checks: Vec::new(),
+            next_subexpr_index: 0,
+            is_mut,
+            target,
+            has_ctx,
+            is_try,
+            is_test_with_index: false,
+            is_test_by_ref,
+            is_capture_free: false,
+            deferred_actions: Vec::new(),
+        }
+    }
+
+    /// State for matching without capturing, where `capture`, `capture_struct`,
+    /// `variants` and `count` groups match like the groups they are attached to
+    pub fn without_captures(self) -> Self {
+        Self {
+            capture_sink: None,
+            is_capture_free: true,
+            ..self
+        }
+    }
+
     fn set_next_subexpr(&mut self, prefix: &str) {
         self.prev_subexpr_matches_ident = Some(format_ident!(
             "__{prefix}_subexpr{}_matches",
@@ -33,10 +103,89 @@ impl State {
         });
     }
 
+    fn capture_place(&self) -> TokenStream {
+        capture_fields::place(&self.capture_path)
+    }
+
+    fn capture_fields_mut(&mut self) -> &mut CaptureFields {
+        self.capture_fields.get_mut(&self.capture_path)
+    }
+
+    /// Inserts a field, which is documented by the summary followed by
+    /// the context of the group, and the notes
+    fn insert_capture_field(
+        &mut self,
+        ident: &Ident,
+        field: Field,
+        summary: String,
+        notes: &[&str],
+    ) -> syn::Result<()> {
+        if self.capture_sink.is_some() && !matches!(field, Field::Range { .. }) {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!("`{}` cannot be used with a capture sink", field.attr_name()),
+            ));
+        }
+        let mut doc = summary;
+        for context in &self.doc_context {
+            doc.push_str(", ");
+            doc.push_str(&context.to_string());
+        }
+        self.capture_fields_mut().insert(ident, field, doc, notes)
+    }
+
+    fn is_repeated(&self) -> bool {
+        self.doc_context
+            .iter()
+            .any(|context| matches!(context, DocContext::Repeat(_)))
+    }
+
+    /// Checks that `required` capture fields are set whenever the expression,
+    /// a `capture_struct` group or a branch of `variants` group matches,
+    /// and that every `capture_struct` group they are nested in is matched
+    /// whenever the enclosing struct is.
+    pub fn check_required(&self, root_guaranteed: BTreeSet<Ident>) -> syn::Result<()> {
+        let mut error: Option<syn::Error> = None;
+        let root_check = (Vec::new(), root_guaranteed);
+        let checks: Vec<_> = self.required_checks.iter().chain([&root_check]).collect();
+        let guaranteed_at = |path: &[Segment]| {
+            checks
+                .iter()
+                .find(|(check_path, _)| check_path.as_slice() == path)
+                .map(|(_, guaranteed)| guaranteed)
+        };
+        let is_struct_guaranteed = |path: &[Segment]| {
+            path.iter()
+                .enumerate()
+                .all(|(index, segment)| match segment {
+                    Segment::Field(ident) => guaranteed_at(&path[..index])
+                        .map_or(false, |guaranteed| guaranteed.contains(ident)),
+                    Segment::Variant { .. } => true,
+                })
+        };
+        for (path, guaranteed) in &checks {
+            let is_struct_guaranteed = is_struct_guaranteed(path);
+            for ident in self.capture_fields.get(path).required_idents() {
+                if !is_struct_guaranteed || !guaranteed.contains(ident) {
+                    let new_error = syn::Error::new_spanned(
+                        ident,
+                        "`required` capture is not set on every match",
+                    );
+                    match &mut error {
+                        Some(error) => error.combine(new_error),
+                        None => error = Some(new_error),
+                    }
+                }
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+
     fn push_group_all(
         &mut self,
         all: &Punctuated<Expr, Token![,]>,
     ) -> syn::Result<BTreeSet<Ident>> {
+        let capture_place = self.capture_place();
         let mut inner_capture_idents = BTreeSet::new();
         let mut block = TokenStream::new();
         let mut new_branch_capture_idents = vec![];
@@ -45,15 +194,31 @@ impl State {
             let branch_capture_idents = self.push_tree(expr.try_into()?)?;
             let branch_matches_ident = self.unwrap_prev_subexpr_matches_ident();
 
+            // A sink is snapshotted once, before the first branch that captures
+            let was_captured = !inner_capture_idents.is_empty();
             new_branch_capture_idents.clear();
             for ident in branch_capture_idents {
                 if inner_capture_idents.insert(ident.clone()) {
                     new_branch_capture_idents.push(ident);
                 }
             }
-            block.extend(cache::Capture::new(&new_branch_capture_idents).cache());
+            if self.capture_sink.is_none() || !was_captured {
+                block.extend(
+                    cache::Capture::new(
+                        &capture_place,
+                        &new_branch_capture_idents,
+                        self.capture_sink.as_ref(),
+                    )
+                    .cache(),
+                );
+            }
 
-            let capture_restore = cache::Capture::new(&inner_capture_idents).restore();
+            let capture_restore = cache::Capture::new(
+                &capture_place,
+                &inner_capture_idents,
+                self.capture_sink.as_ref(),
+            )
+            .restore();
             block.extend(quote! {
                 if !self.#branch_matches_ident() {
                     #capture_restore
@@ -67,11 +232,25 @@ impl State {
         Ok(inner_capture_idents)
     }
 
+    fn push_any_branch(
+        &mut self,
+        any: &Punctuated<Expr, Token![,]>,
+        index: usize,
+    ) -> syn::Result<BTreeSet<Ident>> {
+        self.doc_context.push(DocContext::Branch {
+            index,
+            count: any.len(),
+        });
+        let branch_capture_idents = self.push_tree((&any[index]).try_into()?);
+        self.doc_context.pop();
+        branch_capture_idents
+    }
+
     fn push_group_any(
         &mut self,
         any: &Punctuated<Expr, Token![,]>,
     ) -> syn::Result<BTreeSet<Ident>> {
-        let vars = cache::Vars::default();
+        let vars = cache::Vars::new(self.target.as_ref());
 
         let mut block = TokenStream::new();
         if any.len() > 1 {
@@ -80,8 +259,8 @@ impl State {
         let mut inner_capture_idents = BTreeSet::new();
 
         let mut restoring_branch =
-            |expr: &Expr, cache_other_vars_restore: &TokenStream| -> syn::Result<()> {
-                inner_capture_idents.append(&mut self.push_tree(expr.try_into()?)?);
+            |index: usize, cache_other_vars_restore: &TokenStream| -> syn::Result<()> {
+                inner_capture_idents.append(&mut self.push_any_branch(any, index)?);
                 let branch_subexpr_matches = self.unwrap_prev_subexpr_matches_ident();
                 block.extend(quote! {
                     if self.#branch_subexpr_matches() {
@@ -94,15 +273,15 @@ impl State {
 
         if any.len() > 2 {
             let vars_restore = vars.restore_clone();
-            for expr in any.iter().take(any.len() - 2) {
-                restoring_branch(expr, &vars_restore)?;
+            for index in 0..any.len() - 2 {
+                restoring_branch(index, &vars_restore)?;
             }
         }
         if any.len() > 1 {
-            restoring_branch(&any[any.len() - 2], &vars.restore())?;
+            restoring_branch(any.len() - 2, &vars.restore())?;
         }
         if !any.is_empty() {
-            inner_capture_idents.append(&mut self.push_tree(any.last().unwrap().try_into()?)?);
+            inner_capture_idents.append(&mut self.push_any_branch(any, any.len() - 1)?);
             let branch_subexpr_matches = self.unwrap_prev_subexpr_matches_ident();
             block.extend(quote! {
                 if self.#branch_subexpr_matches() {
@@ -130,51 +309,325 @@ impl State {
         &mut self,
         group: &Group,
         repeat: &Repeat,
+        count_ident: Option<&Ident>,
     ) -> syn::Result<BTreeSet<Ident>> {
-        let inner_capture_idents = self.push_group(group)?;
+        self.doc_context
+            .push(DocContext::Repeat(quantifier(repeat)));
+        let inner_capture_idents = self.push_group(group);
+        self.doc_context.pop();
+        let mut inner_capture_idents = inner_capture_idents?;
         self.push_subexpr_matches(
             "repeat",
             &repeat::expand_block(
                 repeat,
                 self.unwrap_prev_subexpr_matches_ident(),
+                &self.capture_place(),
                 &inner_capture_idents,
+                self.capture_sink.as_ref(),
+                count_ident,
+                self.target.as_ref(),
             ),
         );
+        inner_capture_idents.extend(count_ident.cloned());
         Ok(inner_capture_idents)
     }
 
+    fn push_counted_group(
+        &mut self,
+        group: &Group,
+        count_ident: Ident,
+    ) -> syn::Result<BTreeSet<Ident>> {
+        let repeated = match group {
+            Group::All(exactly_one) | Group::Any(exactly_one) if exactly_one.len() == 1 => {
+                match (&exactly_one[0]).try_into()? {
+                    Tree::Group(inner, Some(HitoriAttribute::Repeat(repeat))) => {
+                        Some((inner, repeat))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let (inner, repeat) = repeated.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &count_ident,
+                "count must contain exactly one repeated group \
+                (e.g. `#[hitori::count(n)] (#[hitori::repeat(ge = 0)] _group,)`)",
+            )
+        })?;
+        self.insert_capture_field(
+            &count_ident,
+            Field::Count,
+            format!(
+                "Number of `{}` repetitions of `{}`",
+                quantifier(&repeat),
+                group_pattern_string(&inner)?,
+            ),
+            &[],
+        )?;
+        self.push_repeated_group(&inner, &repeat, Some(&count_ident))
+    }
+
     fn push_captured_group(
         &mut self,
         group: &Group,
-        capture_idents: Punctuated<Ident, Token![,]>,
+        capture: Capture,
     ) -> syn::Result<BTreeSet<Ident>> {
         let mut inner_capture_idents = self.push_group(group)?;
+        let capture_idents = capture.idents;
         if capture_idents.is_empty() {
             return Ok(inner_capture_idents);
         }
+        let doc = format!("Range of `{}`", group_pattern_string(group)?);
+        let mut notes = vec![];
+        match capture.keep {
+            Keep::First => notes.push("The first match is kept."),
+            Keep::Last if self.is_repeated() => notes.push("The last match is kept."),
+            Keep::Last => (),
+            Keep::Longest => notes.push("The longest match is kept."),
+        }
+        if !capture.is_required {
+            notes.push("`None` if the group was not matched.");
+        }
+        for ident in &capture_idents {
+            self.insert_capture_field(
+                ident,
+                Field::Range {
+                    is_required: capture.is_required,
+                    is_pair: self.is_capture_pairs,
+                },
+                doc.clone(),
+                &notes,
+            )?;
+        }
 
+        let capture_place = self.capture_place();
         let inner_matches_ident = self.unwrap_prev_subexpr_matches_ident();
-        let capture_idents_xcpt_last_iter = capture_idents.iter().take(capture_idents.len() - 1);
-        let last_capture_ident = capture_idents.last().unwrap();
-
-        self.push_subexpr_matches("capture", &quote! {
-            let start = ::core::clone::Clone::clone(&self.__end);
-            if !self.#inner_matches_ident() {
-                return false;
-            }
-            #(
-                self.__capture.#capture_idents_xcpt_last_iter =
-                    Some(::core::clone::Clone::clone(&start)..::core::clone::Clone::clone(&self.__end));
-            )*
-            self.__capture.#last_capture_ident =
-                Some(start..::core::clone::Clone::clone(&self.__end));
-            true
-        });
+        let end = quote! { ::core::clone::Clone::clone(&self.__end) };
+        let range = |start: TokenStream| {
+            if self.is_capture_pairs {
+                quote! { (#start, #end) }
+            } else {
+                quote! { #start..#end }
+            }
+        };
+        let cloned_start_range = range(quote! { ::core::clone::Clone::clone(&start) });
+        let start_range = range(quote! { start });
+        let set = match (&self.capture_sink, capture.keep) {
+            (Some(hitori_ident), Keep::Last) if !capture.is_required => {
+                let capture_names = capture_idents.iter().map(ToString::to_string);
+                quote! {
+                    #(
+                        #hitori_ident::CaptureSink::set(
+                            &mut #capture_place,
+                            #capture_names,
+                            ::core::clone::Clone::clone(&start)..::core::clone::Clone::clone(&self.__end),
+                        );
+                    )*
+                }
+            }
+            (Some(_), _) => {
+                return Err(syn::Error::new_spanned(
+                    &capture_idents[0],
+                    "`keep` and `required` cannot be used with a capture sink",
+                ))
+            }
+            (None, Keep::Last) if capture.is_required => {
+                let capture_idents_xcpt_last_iter =
+                    capture_idents.iter().take(capture_idents.len() - 1);
+                let last_capture_ident = capture_idents.last().unwrap();
+                quote! {
+                    #(
+                        #capture_place.#capture_idents_xcpt_last_iter = #cloned_start_range;
+                    )*
+                    #capture_place.#last_capture_ident = #start_range;
+                }
+            }
+            (None, Keep::Last) => {
+                let capture_idents_xcpt_last_iter =
+                    capture_idents.iter().take(capture_idents.len() - 1);
+                let last_capture_ident = capture_idents.last().unwrap();
+                quote! {
+                    #(
+                        #capture_place.#capture_idents_xcpt_last_iter =
+                            Some(#cloned_start_range);
+                    )*
+                    #capture_place.#last_capture_ident = Some(#start_range);
+                }
+            }
+            (None, Keep::First) => {
+                let capture_idents = capture_idents.iter();
+                quote! {
+                    #(
+                        if #capture_place.#capture_idents.is_none() {
+                            #capture_place.#capture_idents = Some(#cloned_start_range);
+                        }
+                    )*
+                }
+            }
+            (None, Keep::Longest) => {
+                let capture_idents = capture_idents.iter();
+                let (prev_start, prev_end) = if self.is_capture_pairs {
+                    (quote! { prev.0 }, quote! { prev.1 })
+                } else {
+                    (quote! { prev.start }, quote! { prev.end })
+                };
+                quote! {
+                    #(
+                        let is_longer = match &#capture_place.#capture_idents {
+                            Some(prev) => {
+                                ::core::clone::Clone::clone(&#prev_end)
+                                    - ::core::clone::Clone::clone(&#prev_start)
+                                    < #end - ::core::clone::Clone::clone(&start)
+                            }
+                            None => true,
+                        };
+                        if is_longer {
+                            #capture_place.#capture_idents = Some(#cloned_start_range);
+                        }
+                    )*
+                }
+            }
+        };
+
+        self.push_subexpr_matches(
+            "capture",
+            &quote! {
+                let start = ::core::clone::Clone::clone(&self.__end);
+                if !self.#inner_matches_ident() {
+                    return false;
+                }
+                #set
+                true
+            },
+        );
 
         inner_capture_idents.extend(capture_idents);
         Ok(inner_capture_idents)
     }
 
+    fn push_group_capture_struct(
+        &mut self,
+        group: &Group,
+        struct_ident: Ident,
+    ) -> syn::Result<BTreeSet<Ident>> {
+        let nested_ident = self.capture_fields_mut().nested_struct_ident(&struct_ident);
+        let doc = format!("Capture of `{}`", group_pattern_string(group)?);
+        self.insert_capture_field(
+            &struct_ident,
+            Field::Struct(CaptureFields::new(nested_ident, doc.clone())),
+            doc,
+            &[],
+        )?;
+        self.capture_path.push(Segment::Field(struct_ident.clone()));
+        let doc_context = mem::take(&mut self.doc_context);
+        let inner_capture_idents = self.push_group(group);
+        self.doc_context = doc_context;
+        if inner_capture_idents.is_ok() {
+            self.required_checks.push((
+                self.capture_path.clone(),
+                required::group_guaranteed_capture_idents(group)?,
+            ));
+        }
+        self.capture_path.pop();
+        inner_capture_idents?;
+        Ok(BTreeSet::from([struct_ident]))
+    }
+
+    fn push_group_variants(
+        &mut self,
+        group: &Group,
+        variants: Variants,
+    ) -> syn::Result<BTreeSet<Ident>> {
+        let Variants {
+            idents: variant_idents,
+            dest: field_ident,
+        } = variants;
+        let any = match group {
+            Group::Any(any) if any.len() == variant_idents.len() => any,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant_idents,
+                    "variants must name each branch of an any-pattern \
+                    (e.g. `#[hitori::variants(A, B)] [_a, _b]`)",
+                ))
+            }
+        };
+
+        let mut branch_docs = vec![];
+        for expr in any.iter() {
+            branch_docs.push(format!(
+                "Capture of `{}` branch",
+                pattern_string::pattern_string(expr)?
+            ));
+        }
+        let fields = self.capture_fields_mut();
+        let enum_ident = fields.nested_struct_ident(&field_ident);
+        let variants = variant_idents
+            .iter()
+            .zip(branch_docs)
+            .map(|(variant_ident, doc)| {
+                (
+                    variant_ident.clone(),
+                    CaptureFields::new(fields.nested_struct_ident(variant_ident), doc),
+                )
+            })
+            .collect();
+        self.insert_capture_field(
+            &field_ident,
+            Field::Variants {
+                enum_ident: enum_ident.clone(),
+                variants,
+            },
+            format!("Matched branch of `{}`", group_pattern_string(group)?),
+            &[],
+        )?;
+        let doc_context = mem::take(&mut self.doc_context);
+
+        let capture_place = self.capture_place();
+        let vars = cache::Vars::new(self.target.as_ref());
+        let vars_restore = vars.restore_clone();
+        let mut block = vars.cache();
+        block.extend(quote! {
+            let #field_ident = ::core::clone::Clone::clone(&#capture_place.#field_ident);
+        });
+
+        for (index, (expr, variant_ident)) in any.iter().zip(variant_idents).enumerate() {
+            self.capture_path.push(Segment::Variant {
+                field_ident: field_ident.clone(),
+                enum_ident: enum_ident.clone(),
+                variant_ident: variant_ident.clone(),
+                index,
+            });
+            let branch_capture_idents = self.push_tree(expr.try_into()?);
+            if branch_capture_idents.is_ok() {
+                self.required_checks.push((
+                    self.capture_path.clone(),
+                    required::guaranteed_capture_idents(expr.try_into()?)?,
+                ));
+            }
+            self.capture_path.pop();
+            branch_capture_idents?;
+
+            let branch_matches_ident = self.unwrap_prev_subexpr_matches_ident();
+            block.extend(quote! {
+                #capture_place.#field_ident = ::core::option::Option::Some(
+                    #enum_ident::#variant_ident(::core::default::Default::default()),
+                );
+                if self.#branch_matches_ident() {
+                    return true;
+                }
+                #vars_restore
+                #capture_place.#field_ident = ::core::clone::Clone::clone(&#field_ident);
+            });
+        }
+
+        self.doc_context = doc_context;
+        block.extend(quote! { false });
+        self.push_subexpr_matches("variants", &block);
+        Ok(BTreeSet::from([field_ident]))
+    }
+
     fn push_positioned_group(
         &mut self,
         group: &Group,
@@ -208,10 +661,116 @@ impl State {
         Ok(inner_capture_idents)
     }
 
+    fn push_group_on_match(
+        &mut self,
+        group: &Group,
+        action: &Expr,
+    ) -> syn::Result<BTreeSet<Ident>> {
+        if !self.is_mut {
+            return Err(syn::Error::new_spanned(
+                action,
+                "`on_match` can only be used with `impl_expr_mut`",
+            ));
+        }
+        let inner_capture_idents = self.push_group(group)?;
+        let run = if matches!(self.target, Some(cache::Target::Deferred)) {
+            let index = self.deferred_actions.len();
+            self.deferred_actions.push(action.to_token_stream());
+            quote! { self.__actions.push((#index, range)); }
+        } else {
+            quote! { (#action)(range); }
+        };
+        let inner_matches_ident = self.unwrap_prev_subexpr_matches_ident();
+        self.push_subexpr_matches(
+            "on_match",
+            &quote! {
+                let start = ::core::clone::Clone::clone(&self.__end);
+                if !self.#inner_matches_ident() {
+                    return false;
+                }
+                let range = start..::core::clone::Clone::clone(&self.__end);
+                #run
+                true
+            },
+        );
+        Ok(inner_capture_idents)
+    }
+
+    /// Method running the deferred `on_match` actions in the order
+    /// they were queued, once the whole expression has matched
+    pub fn run_actions_fn(&self) -> Option<TokenStream> {
+        if !matches!(self.target, Some(cache::Target::Deferred)) {
+            return None;
+        }
+        let indices = 0..self.deferred_actions.len();
+        let actions = &self.deferred_actions;
+        Some(quote! {
+            fn __run_actions(&mut self) {
+                for (index, range) in ::core::mem::take(&mut self.__actions) {
+                    match index {
+                        #(#indices => (#actions)(range),)*
+                        _ => ::core::unreachable!(),
+                    }
+                }
+            }
+        })
+    }
+
+    fn push_group_with_index(&mut self, group: &Group) -> syn::Result<BTreeSet<Ident>> {
+        let was_test_with_index = mem::replace(&mut self.is_test_with_index, true);
+        let inner_capture_idents = self.push_group(group);
+        self.is_test_with_index = was_test_with_index;
+        inner_capture_idents
+    }
+
+    fn push_group_by_ref(&mut self, group: &Group) -> syn::Result<BTreeSet<Ident>> {
+        let was_test_by_ref = mem::replace(&mut self.is_test_by_ref, true);
+        let inner_capture_idents = self.push_group(group);
+        self.is_test_by_ref = was_test_by_ref;
+        inner_capture_idents
+    }
+
     fn push_test(&mut self, test: &Expr) {
+        let ch = if self.is_test_by_ref {
+            quote! { &next.1 }
+        } else {
+            quote! { next.1 }
+        };
+        let args = if self.is_test_with_index {
+            quote! { ::core::clone::Clone::clone(&next.0), #ch }
+        } else {
+            ch
+        };
+        let ctx = self.has_ctx.then(|| {
+            quote! {
+                #[allow(unused_variables)]
+                let ctx = &self.__ctx;
+            }
+        });
+        let (error_check, test_result) = if self.is_try {
+            (
+                Some(quote! {
+                    if self.__error.is_some() {
+                        return false;
+                    }
+                }),
+                quote! {
+                    match (#test)(#args) {
+                        ::core::result::Result::Ok(is_matched) => is_matched,
+                        ::core::result::Result::Err(err) => {
+                            self.__error = ::core::option::Option::Some(err);
+                            false
+                        }
+                    }
+                },
+            )
+        } else {
+            (None, quote! { (#test)(#args) })
+        };
         self.push_subexpr_matches(
             "test",
             &quote! {
+                #error_check
                 let next = if let ::core::option::Option::Some(next) =
                     ::core::iter::Iterator::next(&mut self.__iter)
                 {
@@ -219,7 +778,8 @@ impl State {
                 } else {
                     return false;
                 };
-                if (#test)(next.1) {
+                #ctx
+                if #test_result {
                     self.__is_first = false;
                     self.__end = next.0;
                     true
@@ -232,15 +792,36 @@ impl State {
 
     pub(super) fn push_tree(&mut self, tree: Tree) -> syn::Result<BTreeSet<Ident>> {
         match tree {
+            Tree::Group(
+                group,
+                Some(
+                    HitoriAttribute::Capture(_)
+                    | HitoriAttribute::CaptureStruct(_)
+                    | HitoriAttribute::Variants(_)
+                    | HitoriAttribute::Count(_),
+                ),
+            ) if self.is_capture_free => self.push_group(&group),
             Tree::Group(group, maybe_attr) => match maybe_attr {
                 Some(attr) => match attr {
-                    HitoriAttribute::Repeat(repeat) => self.push_repeated_group(&group, &repeat),
-                    HitoriAttribute::Capture(capture_idents) => {
-                        self.push_captured_group(&group, capture_idents)
+                    HitoriAttribute::Repeat(repeat) => {
+                        self.push_repeated_group(&group, &repeat, None)
+                    }
+                    HitoriAttribute::Count(count_ident) => {
+                        self.push_counted_group(&group, count_ident)
+                    }
+                    HitoriAttribute::Capture(capture) => self.push_captured_group(&group, capture),
+                    HitoriAttribute::CaptureStruct(struct_ident) => {
+                        self.push_group_capture_struct(&group, struct_ident)
+                    }
+                    HitoriAttribute::Variants(variants) => {
+                        self.push_group_variants(&group, variants)
                     }
                     HitoriAttribute::Position(position) => {
                         self.push_positioned_group(&group, &position)
                     }
+                    HitoriAttribute::WithIndex => self.push_group_with_index(&group),
+                    HitoriAttribute::ByRef => self.push_group_by_ref(&group),
+                    HitoriAttribute::OnMatch(action) => self.push_group_on_match(&group, &action),
                 },
                 None => self.push_group(&group),
             },
@@ -251,3 +832,20 @@ impl State {
         }
     }
 }
+
+/// Repetition or any-pattern branch a capture is inside of
+enum DocContext {
+    Repeat(String),
+    Branch { index: usize, count: usize },
+}
+
+impl fmt::Display for DocContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Repeat(quantifier) => write!(f, "inside `{quantifier}` repetition"),
+            Self::Branch { index, count } => {
+                write!(f, "in branch {} of {count} alternatives", index + 1)
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
+pub mod capture;
 pub mod position;
 pub mod repeat;
+pub mod variants;
 
 mod args;
 
@@ -8,16 +10,17 @@ use crate::utils::{
     type_as_type_path,
 };
 use args::Args;
+pub use args::Transactional;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, ToTokens as _};
 use syn::{
     parse2,
     punctuated::{self, Punctuated},
-    Expr, GenericParam, ImplItem, ImplItemConst, ItemImpl, Path, PathArguments, PathSegment, Token,
-    Type, TypePath, Visibility, WhereClause,
+    Expr, GenericParam, ImplItem, ImplItemConst, ImplItemFn, ImplItemType, ItemImpl, Meta, Path,
+    PathArguments, PathSegment, Token, Type, TypePath, Visibility, WhereClause,
 };
 
-fn trait_ident_and_args(mut path: Path) -> syn::Result<(Ident, [Type; 2])> {
+fn trait_ident_and_args(mut path: Path) -> syn::Result<(Ident, Vec<Type>)> {
     Err(
         if path.segments.len() != 1 || path.leading_colon.is_some() {
             syn::Error::new_spanned(path, "expected identifier")
@@ -26,13 +29,16 @@ fn trait_ident_and_args(mut path: Path) -> syn::Result<(Ident, [Type; 2])> {
         {
             match arguments {
                 PathArguments::AngleBracketed(args) => {
-                    if args.args.len() == 2 {
-                        let mut args = args.args.into_iter();
-                        let idx_arg = generic_arg_try_into_type(args.next().unwrap())?;
-                        let ch_arg = generic_arg_try_into_type(args.next().unwrap())?;
-                        return Ok((ident, [idx_arg, ch_arg]));
+                    let args_len = if ident == "ExprWith" { 3 } else { 2 };
+                    if args.args.len() == args_len {
+                        return args
+                            .args
+                            .into_iter()
+                            .map(generic_arg_try_into_type)
+                            .collect::<syn::Result<_>>()
+                            .map(|args| (ident, args));
                     }
-                    syn::Error::new_spanned(args, "expected 2 arguments")
+                    syn::Error::new_spanned(args, format!("expected {args_len} arguments"))
                 }
                 PathArguments::Parenthesized(args) => {
                     syn::Error::new_spanned(args, "expected angle brackets around arguments")
@@ -91,10 +97,34 @@ fn const_expr(items: Vec<ImplItem>) -> syn::Result<Expr> {
     })
 }
 
+fn take_reset_fn(items: &mut Vec<ImplItem>) -> Option<ImplItemFn> {
+    let index = items
+        .iter()
+        .position(|item| matches!(item, ImplItem::Fn(fn_) if fn_.sig.ident == "reset"))?;
+    match items.remove(index) {
+        ImplItem::Fn(fn_) => Some(fn_),
+        _ => unreachable!(),
+    }
+}
+
+fn take_error_ty(items: &mut Vec<ImplItem>) -> Option<ImplItemType> {
+    let index = items
+        .iter()
+        .position(|item| matches!(item, ImplItem::Type(ty) if ty.ident == "Error"))?;
+    match items.remove(index) {
+        ImplItem::Type(ty) => Some(ty),
+        _ => unreachable!(),
+    }
+}
+
 pub struct Output {
     pub is_mut: bool,
     pub capture_vis: Visibility,
     pub capture_ident: Ident,
+    pub capture_sink: Option<Type>,
+    pub capture_derives: Punctuated<Path, Token![,]>,
+    pub capture_attrs: Punctuated<Meta, Token![,]>,
+    pub is_capture_pairs: bool,
     pub capture_idx_ident: Ident,
     pub self_ty: Box<Type>,
     pub trait_ident: Ident,
@@ -102,50 +132,117 @@ pub struct Output {
     pub idx_ty: Type,
     pub is_idx_generic: bool,
     pub ch_ty: Type,
+    pub ctx_ty: Option<Type>,
     pub expr: Expr,
+    pub reset_fn: Option<ImplItemFn>,
+    pub error_ty: Option<ImplItemType>,
+    pub is_test_by_ref: bool,
+    pub transactional: Option<Transactional>,
+    pub automaton: Option<Ident>,
     pub wrapper_ident: Ident,
     pub generic_params: Punctuated<GenericParam, Token![,]>,
     pub where_clause: Option<WhereClause>,
 }
 
 impl Output {
-    fn new(is_mut: bool, args: Args, item: ItemImpl) -> syn::Result<Self> {
+    fn new(is_mut: bool, args: Args, mut item: ItemImpl) -> syn::Result<Self> {
         let iter_ident = ident_not_in_generic_params(&item.generics.params, "I".into());
         let wrapper_ident = ident_not_in_generic_params(&item.generics.params, "Self_".into());
 
-        let (trait_ident, [idx_ty, ch_ty]) = trait_ident_and_args(
+        let (trait_ident, trait_args) = trait_ident_and_args(
             item.trait_
                 .ok_or_else(|| syn::Error::new_spanned(&item.self_ty, "not a trait impl"))?
                 .1,
         )?;
+        let mut trait_args = trait_args.into_iter();
+        let idx_ty = trait_args.next().unwrap();
+        let ch_ty = trait_args.next().unwrap();
+        let ctx_ty = trait_args.next();
 
         if is_mut {
-            if trait_ident != "ExprMut" {
-                return Err(syn::Error::new_spanned(trait_ident, "not `ExprMut`"));
+            if trait_ident != "ExprMut" && trait_ident != "TryExprMut" {
+                return Err(syn::Error::new_spanned(
+                    trait_ident,
+                    "neither `ExprMut`, nor `TryExprMut`",
+                ));
+            }
+        } else if trait_ident != "Expr" && trait_ident != "ExprWith" {
+            return Err(syn::Error::new_spanned(
+                trait_ident,
+                "neither `Expr`, nor `ExprWith`",
+            ));
+        } else if args.transactional.is_some() {
+            return Err(syn::Error::new_spanned(
+                trait_ident,
+                "`transactional` can only be used with `impl_expr_mut`",
+            ));
+        }
+
+        if let Some(automaton) = &args.automaton {
+            let is_ch_char = matches!(
+                &ch_ty,
+                Type::Path(TypePath { qself: None, path }) if path.is_ident("char")
+            );
+            let error = if is_mut || ctx_ty.is_some() {
+                Some("`automaton` can only be used with `impl_expr` implementing `Expr`")
+            } else if !is_ch_char {
+                Some("`automaton` can only be used with `char` characters")
+            } else if args.is_test_by_ref {
+                Some("`automaton` cannot be used with `by_ref`")
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                return Err(syn::Error::new_spanned(automaton, error));
             }
-        } else if trait_ident != "Expr" {
-            return Err(syn::Error::new_spanned(trait_ident, "not `Expr`"));
         }
 
         let is_idx_generic = has_type_any_generic_params(&item.generics.params, &idx_ty);
 
+        let has_capture_struct_args = args.capture_vis.is_some()
+            || !args.capture_derives.is_empty()
+            || !args.capture_attrs.is_empty();
         let vis = args
             .capture_vis
             .unwrap_or_else(|| Visibility::Public(<Token![pub]>::default()));
 
-        let capture_ident = if let Some(ident) = args.capture_ident {
-            ident
-        } else {
-            match type_as_type_path(&item.self_ty) {
-                Some(TypePath {
-                    path: Path { segments, .. },
-                    ..
-                }) if !segments.is_empty() => {
-                    let self_ident = &segments.last().unwrap().ident;
-                    format_ident!("{self_ident}Capture")
+        let (capture_ident, capture_sink) = match (args.capture_ident, args.capture_sink) {
+            (Some(ident), Some(ty)) => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format!("`with_capture = \"{ident}\"` cannot be used with a capture sink"),
+                ))
+            }
+            (None, Some(ty)) => {
+                if let Some(capture_pairs) = &args.capture_pairs {
+                    return Err(syn::Error::new_spanned(
+                        capture_pairs,
+                        "`capture_pairs` cannot be used with a capture sink",
+                    ));
+                }
+                if has_capture_struct_args {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "`with_capture_vis`, `with_capture_derive` and `with_capture_attr` \
+                        cannot be used with a capture sink",
+                    ));
                 }
-                _ => format_ident!("Capture"),
+                (format_ident!("Capture"), Some(ty))
             }
+            (Some(ident), None) => (ident, None),
+            (None, None) => (
+                match type_as_type_path(&item.self_ty) {
+                    Some(TypePath {
+                        path: Path { segments, .. },
+                        ..
+                    }) if !segments.is_empty() => {
+                        let self_ident = &segments.last().unwrap().ident;
+                        format_ident!("{self_ident}Capture")
+                    }
+                    _ => format_ident!("Capture"),
+                },
+                None,
+            ),
         };
 
         let capture_idx_ident = if is_idx_generic
@@ -159,10 +256,28 @@ impl Output {
             format_ident!("Idx")
         };
 
+        let reset_fn = if is_mut {
+            take_reset_fn(&mut item.items)
+        } else {
+            None
+        };
+
+        let error_ty = if trait_ident == "TryExprMut" {
+            Some(take_error_ty(&mut item.items).ok_or_else(|| {
+                syn::Error::new_spanned(&trait_ident, "expected `type Error` item")
+            })?)
+        } else {
+            None
+        };
+
         const_expr(item.items).map(|expr| Output {
             is_mut,
             capture_vis: vis,
             capture_ident,
+            capture_sink,
+            capture_derives: args.capture_derives,
+            capture_attrs: args.capture_attrs,
+            is_capture_pairs: args.capture_pairs.is_some(),
             capture_idx_ident,
             self_ty: item.self_ty,
             trait_ident,
@@ -170,7 +285,13 @@ impl Output {
             idx_ty,
             is_idx_generic,
             ch_ty,
+            ctx_ty,
             expr,
+            reset_fn,
+            error_ty,
+            is_test_by_ref: args.is_test_by_ref,
+            transactional: args.transactional,
+            automaton: args.automaton,
             wrapper_ident,
             generic_params: item.generics.params,
             where_clause: item.generics.where_clause,
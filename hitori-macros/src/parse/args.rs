@@ -3,12 +3,34 @@ use proc_macro2::Ident;
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    Expr, ExprLit, Lit, Meta, MetaNameValue, Token, Visibility,
+    Expr, ExprLit, Lit, Meta, MetaNameValue, Path, Token, Type, Visibility,
 };
 
+pub enum Transactional {
+    Clone,
+    Checkpoint,
+}
+
+impl Transactional {
+    fn set(transactional: &mut Option<Self>, path: &Path, value: Self) -> syn::Result<()> {
+        if transactional.is_some() {
+            return Err(syn::Error::new_spanned(path, "duplicate `transactional`"));
+        }
+        *transactional = Some(value);
+        Ok(())
+    }
+}
+
 pub struct Args {
     pub capture_vis: Option<Visibility>,
     pub capture_ident: Option<Ident>,
+    pub capture_sink: Option<Type>,
+    pub capture_derives: Punctuated<Path, Token![,]>,
+    pub capture_attrs: Punctuated<Meta, Token![,]>,
+    pub capture_pairs: Option<Ident>,
+    pub is_test_by_ref: bool,
+    pub transactional: Option<Transactional>,
+    pub automaton: Option<Ident>,
 }
 
 impl TryFrom<Punctuated<Meta, Token![,]>> for Args {
@@ -16,7 +38,14 @@ impl TryFrom<Punctuated<Meta, Token![,]>> for Args {
 
     fn try_from(args: Punctuated<Meta, Token![,]>) -> syn::Result<Self> {
         let mut capture_ident = None;
+        let mut capture_sink = None;
         let mut capture_vis = None;
+        let mut capture_derives = None;
+        let mut capture_attrs = None;
+        let mut capture_pairs = None;
+        let mut is_test_by_ref = false;
+        let mut transactional = None;
+        let mut automaton = None;
 
         for arg in &args {
             match arg {
@@ -34,6 +63,15 @@ impl TryFrom<Punctuated<Meta, Token![,]>> for Args {
                         } else {
                             return Err(syn::Error::new_spanned(path, "duplicate `with_capture`"));
                         }
+                    } else if path_eq_ident_str(path, "with_capture_sink") {
+                        if capture_sink.is_none() {
+                            capture_sink = Some(s.parse()?);
+                        } else {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                "duplicate `with_capture_sink`",
+                            ));
+                        }
                     } else if path_eq_ident_str(path, "with_capture_vis") {
                         if capture_vis.is_none() {
                             capture_vis = Some(s.parse()?);
@@ -43,12 +81,67 @@ impl TryFrom<Punctuated<Meta, Token![,]>> for Args {
                                 "duplicate `with_capture_vis`",
                             ));
                         }
+                    } else if path_eq_ident_str(path, "with_capture_derive") {
+                        if capture_derives.is_none() {
+                            capture_derives = Some(s.parse_with(Punctuated::parse_terminated)?);
+                        } else {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                "duplicate `with_capture_derive`",
+                            ));
+                        }
+                    } else if path_eq_ident_str(path, "with_capture_attr") {
+                        if capture_attrs.is_none() {
+                            capture_attrs = Some(s.parse_with(Punctuated::parse_terminated)?);
+                        } else {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                "duplicate `with_capture_attr`",
+                            ));
+                        }
+                    } else if path_eq_ident_str(path, "transactional") {
+                        let value = match s.value().as_str() {
+                            "clone" => Transactional::Clone,
+                            "checkpoint" => Transactional::Checkpoint,
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    s,
+                                    "expected `\"clone\"` or `\"checkpoint\"`",
+                                ))
+                            }
+                        };
+                        Transactional::set(&mut transactional, path, value)?;
+                    }
+                }
+                Meta::Path(path) if path_eq_ident_str(path, "transactional") => {
+                    Transactional::set(&mut transactional, path, Transactional::Clone)?;
+                }
+                Meta::Path(path) if path_eq_ident_str(path, "automaton") => {
+                    if automaton.is_some() {
+                        return Err(syn::Error::new_spanned(path, "duplicate `automaton`"));
+                    }
+                    automaton = path.get_ident().cloned();
+                }
+                Meta::Path(path) if path_eq_ident_str(path, "capture_pairs") => {
+                    if capture_pairs.is_some() {
+                        return Err(syn::Error::new_spanned(path, "duplicate `capture_pairs`"));
+                    }
+                    capture_pairs = path.get_ident().cloned();
+                }
+                Meta::Path(path) if path_eq_ident_str(path, "by_ref") => {
+                    if is_test_by_ref {
+                        return Err(syn::Error::new_spanned(path, "duplicate `by_ref`"));
                     }
+                    is_test_by_ref = true;
                 }
                 _ => {
                     return Err(syn::Error::new_spanned(
                         arg,
-                        "expected `with_capture` or `with_capture_vis` and literal string value",
+                        "expected `by_ref`, or `transactional`, or `automaton`, \
+                        or `capture_pairs`, or either of \
+                        `with_capture`, `with_capture_sink`, `with_capture_vis`, \
+                        `with_capture_derive`, `with_capture_attr`, `transactional` \
+                        and literal string value",
                     ))
                 }
             }
@@ -57,6 +150,13 @@ impl TryFrom<Punctuated<Meta, Token![,]>> for Args {
         Ok(Self {
             capture_vis,
             capture_ident,
+            capture_sink,
+            capture_derives: capture_derives.unwrap_or_default(),
+            capture_attrs: capture_attrs.unwrap_or_default(),
+            capture_pairs,
+            is_test_by_ref,
+            transactional,
+            automaton,
         })
     }
 }
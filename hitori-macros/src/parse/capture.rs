@@ -0,0 +1,80 @@
+use crate::utils::path_eq_ident_str;
+use proc_macro2::Ident;
+use syn::{parse::Parse, punctuated::Punctuated, Expr, ExprPath, Meta, MetaNameValue, Token};
+
+pub enum Keep {
+    First,
+    Last,
+    Longest,
+}
+
+pub struct Capture {
+    pub idents: Punctuated<Ident, Token![,]>,
+    pub keep: Keep,
+    pub is_required: bool,
+}
+
+impl Parse for Capture {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        let mut idents = Punctuated::new();
+        let mut keep = None;
+        let mut is_required = false;
+
+        for meta in metas {
+            match meta {
+                Meta::Path(path) if path_eq_ident_str(&path, "required") => {
+                    if is_required {
+                        return Err(syn::Error::new_spanned(path, "duplicate `required`"));
+                    }
+                    is_required = true;
+                }
+                Meta::Path(path) if path.get_ident().is_some() => {
+                    idents.push(path.get_ident().unwrap().clone());
+                }
+                Meta::NameValue(MetaNameValue {
+                    path,
+                    value: Expr::Path(ExprPath { path: value, .. }),
+                    ..
+                }) if path_eq_ident_str(&path, "keep") => {
+                    if keep.is_some() {
+                        return Err(syn::Error::new_spanned(path, "duplicate `keep`"));
+                    }
+                    keep = Some(if path_eq_ident_str(&value, "first") {
+                        Keep::First
+                    } else if path_eq_ident_str(&value, "last") {
+                        Keep::Last
+                    } else if path_eq_ident_str(&value, "longest") {
+                        Keep::Longest
+                    } else {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "expected `first`, or `last`, or `longest`",
+                        ));
+                    });
+                }
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "expected an identifier, or `required`, \
+                        or `keep = first|last|longest`",
+                    ))
+                }
+            }
+        }
+
+        let keep = keep.unwrap_or(Keep::Last);
+        if is_required && !matches!(keep, Keep::Last) {
+            return Err(syn::Error::new_spanned(
+                idents,
+                "`required` can only be combined with `keep = last`",
+            ));
+        }
+
+        Ok(Self {
+            idents,
+            keep,
+            is_required,
+        })
+    }
+}
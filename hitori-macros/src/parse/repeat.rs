@@ -1,4 +1,7 @@
-use crate::utils::{expr_add_one_usize, expr_try_from_lit_int_or_lit_str_expr, path_eq_ident_str};
+use crate::utils::{
+    expr_add_one_usize, expr_eval_lit_usize, expr_try_from_lit_int_or_lit_str_expr,
+    path_eq_ident_str,
+};
 use proc_macro2::Literal;
 use std::ops::Bound;
 use syn::{parse::Parse, punctuated::Punctuated, Expr, ExprLit, Lit, MetaNameValue, Token};
@@ -127,6 +130,31 @@ pub enum Repeat {
     },
 }
 
+impl Repeat {
+    /// Lower bound, if it is a literal
+    pub fn lo_lit(&self) -> Option<usize> {
+        match self {
+            Self::Exact(lo_included) | Self::InInclusive { lo_included, .. } => {
+                expr_eval_lit_usize(lo_included)
+            }
+        }
+    }
+
+    /// Inclusive upper bound, if it is a literal
+    pub fn hi_lit(&self) -> Option<usize> {
+        match self {
+            Self::Exact(hi_included) => expr_eval_lit_usize(hi_included),
+            Self::InInclusive {
+                hi_excluded: Some(hi_excluded),
+                ..
+            } => expr_eval_lit_usize(hi_excluded).map(|hi| hi.saturating_sub(1)),
+            Self::InInclusive {
+                hi_excluded: None, ..
+            } => None,
+        }
+    }
+}
+
 impl From<Internal> for Repeat {
     fn from(repeat: Internal) -> Self {
         match repeat {
@@ -0,0 +1,47 @@
+use crate::utils::path_eq_ident_str;
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{parse::Parse, punctuated::Punctuated, Expr, ExprPath, Meta, MetaNameValue, Token};
+
+pub struct Variants {
+    pub idents: Punctuated<Ident, Token![,]>,
+    pub dest: Ident,
+}
+
+impl Parse for Variants {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        let mut idents = Punctuated::new();
+        let mut dest = None;
+
+        for meta in metas {
+            match meta {
+                Meta::Path(path) if path.get_ident().is_some() => {
+                    idents.push(path.get_ident().unwrap().clone());
+                }
+                Meta::NameValue(MetaNameValue {
+                    path,
+                    value: Expr::Path(ExprPath { path: value, .. }),
+                    ..
+                }) if path_eq_ident_str(&path, "dest") && value.get_ident().is_some() => {
+                    if dest.is_some() {
+                        return Err(syn::Error::new_spanned(path, "duplicate `dest`"));
+                    }
+                    dest = value.get_ident().cloned();
+                }
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "expected an identifier, or `dest = field`",
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            idents,
+            dest: dest.unwrap_or_else(|| format_ident!("variant", span = span)),
+        })
+    }
+}
@@ -2,10 +2,10 @@ use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use std::{convert, fmt::Write as _, mem};
 use syn::{
-    punctuated::Punctuated, AssocType, Attribute, BinOp, Expr, ExprBinary, ExprLit,
-    GenericArgument, GenericParam, LifetimeParam, Lit, ParenthesizedGenericArguments, Path,
-    PathArguments, ReturnType, Token, Type, TypeImplTrait, TypeParam, TypeParamBound, TypeParen,
-    TypePath, TypePtr, TypeReference, TypeTraitObject,
+    parse::Parser as _, punctuated::Punctuated, AssocType, Attribute, BinOp, Expr, ExprBinary,
+    ExprLit, GenericArgument, GenericParam, LifetimeParam, Lit, ParenthesizedGenericArguments,
+    Path, PathArguments, ReturnType, Token, Type, TypeImplTrait, TypeParam, TypeParamBound,
+    TypeParen, TypePath, TypePtr, TypeReference, TypeTraitObject,
 };
 
 pub fn hitori_ident() -> Ident {
@@ -82,6 +82,18 @@ pub fn eq_by_fmt<Lhs: ToTokens, Rhs: ToTokens>(lhs: Lhs, rhs: Rhs) -> bool {
     buf[..lhs_end] == buf[lhs_end..]
 }
 
+pub fn snake_to_upper_camel(ident: &Ident) -> String {
+    let mut output = String::new();
+    for word in ident.to_string().split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            output.extend(first.to_uppercase());
+            output.push_str(chars.as_str());
+        }
+    }
+    output
+}
+
 pub fn path_eq_ident_str(path: &Path, ident_str: &str) -> bool {
     path.get_ident()
         .map(|ident| ident == ident_str)
@@ -162,6 +174,21 @@ pub fn expr_add_one_usize(expr: Expr) -> Expr {
     })
 }
 
+pub fn expr_eval_lit_usize(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Add(_),
+            right,
+            ..
+        }) => expr_eval_lit_usize(left)?.checked_add(expr_eval_lit_usize(right)?),
+        _ => None,
+    }
+}
+
 pub fn expr_try_from_lit_int_or_lit_str_expr(expr: Expr) -> syn::Result<Expr> {
     if let Expr::Lit(lit) = &expr {
         match &lit.lit {
@@ -177,6 +204,17 @@ pub fn expr_try_from_lit_int_or_lit_str_expr(expr: Expr) -> syn::Result<Expr> {
     ))
 }
 
+pub fn expr_try_from_lit_str_expr(expr: Expr) -> syn::Result<Expr> {
+    if let Expr::Lit(ExprLit {
+        lit: Lit::Str(s), ..
+    }) = &expr
+    {
+        s.parse()
+    } else {
+        Ok(expr)
+    }
+}
+
 fn is_any_generic_param_eq_ident(
     params: &Punctuated<GenericParam, Token![,]>,
     ident: &Ident,
@@ -291,6 +329,15 @@ pub fn has_generic_arg_any_generic_params(
     }
 }
 
+/// Copies generic parameters by reparsing them, as `syn` is used without `clone-impls`
+pub fn copy_generic_params(
+    params: &Punctuated<GenericParam, Token![,]>,
+) -> Punctuated<GenericParam, Token![,]> {
+    Punctuated::parse_terminated
+        .parse2(params.to_token_stream())
+        .expect("printed generic parameters should parse")
+}
+
 pub fn remove_generic_params_bounds(params: &mut Punctuated<GenericParam, Token![,]>) {
     for param in params {
         if let GenericParam::Type(ty) = param {
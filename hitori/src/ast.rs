@@ -0,0 +1,115 @@
+//! Description of expression patterns available at runtime
+//!
+//! *See [`ExprAst`]*
+
+/// Repetition bound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// Literal [`usize`] bound
+    Lit(usize),
+    /// Source text of an expression bound
+    Expr(&'static str),
+}
+
+/// Capture match that is recorded
+///
+/// *See `keep` argument of `#[hitori::capture]`*
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keep {
+    First,
+    Last,
+    Longest,
+}
+
+/// Node of a pattern description, which mirrors the `PATTERN` constant
+/// of [`impl_expr`](crate::impl_expr) and [`impl_expr_mut`](crate::impl_expr_mut)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// All-pattern (i.e. a tuple)
+    All(&'static [Node]),
+    /// Any-pattern (i.e. an array)
+    Any(&'static [Node]),
+    /// `#[hitori::repeat]` with inclusive `lo` and exclusive `hi` bounds
+    Repeat {
+        lo: Bound,
+        hi: Option<Bound>,
+        node: &'static Node,
+    },
+    /// `#[hitori::capture]`
+    Capture {
+        names: &'static [&'static str],
+        keep: Keep,
+        is_required: bool,
+        node: &'static Node,
+    },
+    /// `#[hitori::capture_struct]`
+    CaptureStruct {
+        name: &'static str,
+        node: &'static Node,
+    },
+    /// `#[hitori::variants]`
+    Variants {
+        name: &'static str,
+        names: &'static [&'static str],
+        node: &'static Node,
+    },
+    /// `#[hitori::count]`
+    Count {
+        name: &'static str,
+        node: &'static Node,
+    },
+    /// `#[hitori::position]`
+    Position {
+        first: bool,
+        last: bool,
+        node: &'static Node,
+    },
+    /// `#[hitori::with_index]`
+    WithIndex(&'static Node),
+    /// `#[hitori::by_ref]`
+    ByRef(&'static Node),
+    /// `#[hitori::on_match]` with the source text of the action
+    OnMatch {
+        action: &'static str,
+        node: &'static Node,
+    },
+    /// Source text of a character test
+    Test(&'static str),
+}
+
+/// Expression with a pattern description
+///
+/// *Implemented by [`impl_expr`](crate::impl_expr) and
+/// [`impl_expr_mut`](crate::impl_expr_mut)*
+pub trait ExprAst<Idx, Ch> {
+    const AST: Node;
+
+    /// Regex-like rendering of the pattern (e.g. `([a-z]+)@<test>`).
+    ///
+    /// [Simple tests](crate#simple-tests) are shown as characters (e.g. `a`)
+    /// or classes (e.g. `[a-z]`), while the other tests are shown as `<test>`.
+    fn pattern_string() -> &'static str;
+}
+
+macro_rules! impl_ast_for_wrapper {
+    ($ty:ty$(, $param:ident)?) => {
+        impl<'a, Idx, Ch, E: ExprAst<Idx, Ch>$(, $param)?> ExprAst<Idx, Ch> for $ty {
+            const AST: Node = E::AST;
+
+            #[inline]
+            fn pattern_string() -> &'static str {
+                E::pattern_string()
+            }
+        }
+    };
+}
+
+impl_ast_for_wrapper!(&'a E);
+
+impl_ast_for_wrapper!(&'a mut E);
+
+impl_ast_for_wrapper!(crate::With<E, Ctx>, Ctx);
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc, doc(cfg(feature = "alloc")))]
+impl_ast_for_wrapper!(alloc::boxed::Box<E>);
@@ -0,0 +1,156 @@
+//! [DOT] rendering of expression patterns (e.g. for [Graphviz])
+//!
+//! *See [`Dot`]*
+//!
+//! [DOT]: https://graphviz.org/doc/info/lang.html
+//! [Graphviz]: https://graphviz.org
+
+use crate::ast::{Bound, ExprAst, Keep, Node};
+use core::fmt::{self, Display, Formatter};
+
+/// Pattern description that is displayed as a DOT graph
+///
+/// Sequences, alternations and attributes (e.g. repetition bounds,
+/// captures and position assertions) are shown as boxes,
+/// while character tests are shown as ellipses with their source text.
+/// Sequences and alternations of a single element (e.g. `(test,)`)
+/// are shown as the element itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dot(pub Node);
+
+impl Dot {
+    /// Pattern description of an expression
+    #[inline]
+    pub fn of<E: ExprAst<Idx, Ch>, Idx, Ch>() -> Self {
+        Self(E::AST)
+    }
+}
+
+/// Escapes everything written into a DOT string literal
+struct Escape<'a, 'f>(&'a mut Formatter<'f>);
+
+impl fmt::Write for Escape<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            match ch {
+                '"' | '\\' => {
+                    self.0.write_char('\\')?;
+                    self.0.write_char(ch)?;
+                }
+                '\n' => self.0.write_str("\\n")?,
+                _ => self.0.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_bound(f: &mut impl fmt::Write, bound: Bound) -> fmt::Result {
+    match bound {
+        Bound::Lit(lit) => write!(f, "{lit}"),
+        Bound::Expr(expr) => write!(f, "({expr})"),
+    }
+}
+
+fn write_names(f: &mut impl fmt::Write, names: &[&str]) -> fmt::Result {
+    for (index, name) in names.iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+        f.write_str(name)?;
+    }
+    Ok(())
+}
+
+fn write_label(f: &mut impl fmt::Write, node: &Node) -> fmt::Result {
+    match node {
+        Node::All(_) => f.write_str("sequence"),
+        Node::Any(_) => f.write_str("alternation"),
+        Node::Repeat { lo, hi, .. } => {
+            f.write_str("repeat ")?;
+            write_bound(f, *lo)?;
+            f.write_str("..")?;
+            match hi {
+                Some(hi) => write_bound(f, *hi),
+                None => Ok(()),
+            }
+        }
+        Node::Capture {
+            names,
+            keep,
+            is_required,
+            ..
+        } => {
+            f.write_str("capture ")?;
+            write_names(f, names)?;
+            match keep {
+                Keep::First => f.write_str("\nkeep = first")?,
+                Keep::Last => (),
+                Keep::Longest => f.write_str("\nkeep = longest")?,
+            }
+            if *is_required {
+                f.write_str("\nrequired")?;
+            }
+            Ok(())
+        }
+        Node::CaptureStruct { name, .. } => write!(f, "capture_struct {name}"),
+        Node::Variants { name, names, .. } => {
+            f.write_str("variants ")?;
+            write_names(f, names)?;
+            write!(f, "\ndest = {name}")
+        }
+        Node::Count { name, .. } => write!(f, "count {name}"),
+        Node::Position { first, last, .. } => f.write_str(match (first, last) {
+            (true, true) => "position first, last",
+            (true, false) => "position first",
+            (false, true) => "position last",
+            (false, false) => "position",
+        }),
+        Node::WithIndex(_) => f.write_str("with_index"),
+        Node::ByRef(_) => f.write_str("by_ref"),
+        Node::OnMatch { action, .. } => write!(f, "on_match\n{action}"),
+        Node::Test(test) => f.write_str(test),
+    }
+}
+
+/// Writes the node and its descendants, returning the next unused id
+fn write_node(f: &mut Formatter<'_>, node: &Node, id: usize) -> Result<usize, fmt::Error> {
+    if let Node::All([node]) | Node::Any([node]) = node {
+        return write_node(f, node, id);
+    }
+    let shape = match node {
+        Node::Test(_) => "ellipse",
+        _ => "box",
+    };
+    write!(f, "  n{id} [shape={shape}, label=\"")?;
+    write_label(&mut Escape(f), node)?;
+    f.write_str("\"];\n")?;
+    let children: &[Node] = match node {
+        Node::All(nodes) | Node::Any(nodes) => nodes,
+        Node::Repeat { node, .. }
+        | Node::Capture { node, .. }
+        | Node::CaptureStruct { node, .. }
+        | Node::Variants { node, .. }
+        | Node::Count { node, .. }
+        | Node::Position { node, .. }
+        | Node::WithIndex(node)
+        | Node::ByRef(node)
+        | Node::OnMatch { node, .. } => core::slice::from_ref(*node),
+        Node::Test(_) => &[],
+    };
+    let mut next_id = id + 1;
+    for child in children {
+        writeln!(f, "  n{id} -> n{next_id};")?;
+        next_id = write_node(f, child, next_id)?;
+    }
+    Ok(next_id)
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Keeps the children of sequences in order
+        f.write_str("digraph {\n  ordering=out;\n")?;
+        write_node(f, &self.0, 0)?;
+        f.write_str("}\n")
+    }
+}
@@ -1,7 +1,9 @@
-use crate::traits::{ExprMut, Match};
+use crate::traits::{ExprMut, Match, TryExprMut};
 
 /// Checks if an [`Iterator`] starts with [`ExprMut`]-matched characters.
 ///
+/// [`ExprMut::reset`] is called before matching.
+///
 /// # Arguments
 ///
 /// - **`start`** – this should be the start of the first character in the `iter`.
@@ -27,15 +29,28 @@ where
     I: IntoIterator<Item = (Idx, Ch)>,
     I::IntoIter: Clone,
 {
+    expr.reset();
     expr.starts_with_mut(start, is_first, iter)
 }
 
+#[inline]
+fn is_too_short<I: Iterator>(iter: &I, min_len: usize) -> bool {
+    matches!(iter.size_hint(), (_, Some(max_len)) if max_len < min_len)
+}
+
 /// Finds the first subsequence of characters that is matched by [`ExprMut`].
 ///
+/// [`ExprMut::reset`] is called before trying each start position,
+/// except for the ones skipped because of [`ExprMut::can_start_with`].
+/// Searching stops once the upper bound of the [`size_hint`] of the `iter`
+/// is less than [`ExprMut::min_len`].
+///
 /// *See [`starts_with`] for arguments description*
+///
+/// [`size_hint`]: Iterator::size_hint
 pub fn find<E, Idx, Ch, I>(
     mut expr: E,
-    mut start: Idx,
+    start: Idx,
     is_first: bool,
     iter: I,
 ) -> Option<Match<Idx, E::Capture, I::IntoIter>>
@@ -44,11 +59,43 @@ where
     Idx: Clone,
     I: IntoIterator<Item = (Idx, Ch)>,
     I::IntoIter: Clone,
+{
+    find_by(&mut expr, start, is_first, iter, E::starts_with_mut)
+}
+
+/// Tries start positions like [`find`] with `matches` until it returns `Some`
+pub(crate) fn find_by<E, Idx, Ch, I, T, F>(
+    expr: &mut E,
+    mut start: Idx,
+    is_first: bool,
+    iter: I,
+    mut matches: F,
+) -> Option<T>
+where
+    E: ExprMut<Idx, Ch>,
+    Idx: Clone,
+    I: IntoIterator<Item = (Idx, Ch)>,
+    I::IntoIter: Clone,
+    F: FnMut(&mut E, Idx, bool, I::IntoIter) -> Option<T>,
 {
     let mut iter = iter.into_iter();
+    let min_len = expr.min_len();
     loop {
-        if let Some(matched) = expr.starts_with_mut(start.clone(), is_first, iter.clone()) {
-            return Some(matched);
+        if is_too_short(&iter, min_len) {
+            return None;
+        }
+        let mut rest = iter.clone();
+        match rest.next() {
+            Some((end, ch)) if !expr.can_start_with(&ch) => {
+                start = end;
+                iter = rest;
+                continue;
+            }
+            _ => (),
+        }
+        expr.reset();
+        if let Some(found) = matches(expr, start.clone(), is_first, iter.clone()) {
+            return Some(found);
         } else if let Some((new_start, _)) = iter.next() {
             start = new_start;
         } else {
@@ -56,3 +103,103 @@ where
         }
     }
 }
+
+/// Like [`starts_with`], but only checks if there is a match.
+///
+/// Calls [`ExprMut::is_match_mut`], which expressions implemented by
+/// the macros make skip capturing altogether.
+///
+/// *See [`starts_with`] for arguments description*
+#[inline]
+pub fn is_match<E, Idx, Ch, I>(mut expr: E, start: Idx, is_first: bool, iter: I) -> bool
+where
+    E: ExprMut<Idx, Ch>,
+    I: IntoIterator<Item = (Idx, Ch)>,
+    I::IntoIter: Clone,
+{
+    expr.reset();
+    expr.is_match_mut(start, is_first, iter)
+}
+
+/// Like [`find`], but only checks if there is a match.
+///
+/// *See [`is_match`]*
+#[inline]
+pub fn contains<E, Idx, Ch, I>(mut expr: E, start: Idx, is_first: bool, iter: I) -> bool
+where
+    E: ExprMut<Idx, Ch>,
+    Idx: Clone,
+    I: IntoIterator<Item = (Idx, Ch)>,
+    I::IntoIter: Clone,
+{
+    find_by(
+        &mut expr,
+        start,
+        is_first,
+        iter,
+        |expr, start, is_first, iter| expr.is_match_mut(start, is_first, iter).then_some(()),
+    )
+    .is_some()
+}
+
+/// Like [`starts_with`], but for [`TryExprMut`].
+///
+/// Returns the error of the first failed test, if any.
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn try_starts_with<E, Idx, Ch, I>(
+    mut expr: E,
+    start: Idx,
+    is_first: bool,
+    iter: I,
+) -> Result<Option<Match<Idx, E::Capture, I::IntoIter>>, E::Error>
+where
+    E: TryExprMut<Idx, Ch>,
+    I: IntoIterator<Item = (Idx, Ch)>,
+    I::IntoIter: Clone,
+{
+    expr.reset();
+    expr.try_starts_with_mut(start, is_first, iter)
+}
+
+/// Like [`find`], but for [`TryExprMut`].
+///
+/// Stops searching at the first failed test and returns its error.
+#[allow(clippy::type_complexity)]
+pub fn try_find<E, Idx, Ch, I>(
+    mut expr: E,
+    mut start: Idx,
+    is_first: bool,
+    iter: I,
+) -> Result<Option<Match<Idx, E::Capture, I::IntoIter>>, E::Error>
+where
+    E: TryExprMut<Idx, Ch>,
+    Idx: Clone,
+    I: IntoIterator<Item = (Idx, Ch)>,
+    I::IntoIter: Clone,
+{
+    let mut iter = iter.into_iter();
+    let min_len = expr.min_len();
+    loop {
+        if is_too_short(&iter, min_len) {
+            return Ok(None);
+        }
+        let mut rest = iter.clone();
+        match rest.next() {
+            Some((end, ch)) if !expr.can_start_with(&ch) => {
+                start = end;
+                iter = rest;
+                continue;
+            }
+            _ => (),
+        }
+        expr.reset();
+        if let Some(matched) = expr.try_starts_with_mut(start.clone(), is_first, iter.clone())? {
+            return Ok(Some(matched));
+        } else if let Some((new_start, _)) = iter.next() {
+            start = new_start;
+        } else {
+            return Ok(None);
+        }
+    }
+}
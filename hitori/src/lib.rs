@@ -10,9 +10,21 @@
 //! sequence of `a`s longer than zero. With [hitori], however, `a*` would consume
 //! all the `a`s, and the expression won't match.
 //!
+//! Patterns made only of [simple tests](#simple-tests) (with no attributes other than
+//! `repeat` with literal bounds) could be compiled into a deterministic automaton by passing
+//! `automaton` argument to [`impl_expr`], which doesn't have this limitation.
+//!
 //! Step-by step pattern matching also leads to diminished performance when matching
 //! large texts with an expression that contains repetitions of characters frequent in the text.
 //!
+//! # Simple tests
+//!
+//! A test is simple if it only compares the character with literals (`|ch| ch == 'a'`),
+//! checks literal ranges (`|ch| ('a'..='z').contains(&ch)`) or calls `is_ascii_*` methods
+//! (`|ch| ch.is_ascii_digit()`), possibly joined with `||`. The macros know which characters
+//! such tests accept, so they can reject patterns that never match, find start characters,
+//! render the tests as classes and compile patterns into an automaton.
+//!
 //! # Crate features
 //!
 //! - **`alloc`** *(enabled by default)* – string replace functions and blanket implementations
@@ -20,6 +32,7 @@
 //! - **`macros`** *(enabled by default)* – [`impl_expr_mut`] and [`impl_expr`] macros.
 //! - **`find-hitori`** – finds hitori package to be used in macros
 //!   even if it has been renamed in Cargo.toml. **`macros`** feature is required.
+//! - **`dot`** – [DOT](dot) rendering of expression patterns.
 //!
 //! [examples]: https://docs.rs/hitori-examples
 //! [hitori]: https://docs.rs/hitori
@@ -40,22 +53,54 @@ core::compile_error!(
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod ast;
+#[cfg(feature = "dot")]
+#[cfg_attr(doc, doc(cfg(feature = "dot")))]
+pub mod dot;
 pub mod string;
 
 mod generic;
 mod traits;
 
-pub use generic::{find, starts_with};
-pub use traits::{Expr, ExprMut, Match};
+pub use generic::{contains, find, is_match, starts_with, try_find, try_starts_with};
+pub use traits::{
+    CaptureSink, Captures, CapturesIter, Checkpoint, Expr, ExprLen, ExprMut, ExprWith, Literal,
+    MapIdx, Match, TryExprMut, With,
+};
 
 /// Implements [`Expr`] and [`ExprMut`] for the type.
 ///
+/// Alternatively, implements [`ExprWith`] if it is the annotated trait impl.
+/// Tests of such expression can read the context through `ctx` reference.
+///
+/// [`ExprLen`] and [`ast::ExprAst`] are implemented as well. The implementation
+/// and capture struct are documented with the pattern, its captures
+/// and position assertions.
+///
 /// *See [examples] for code samples along with impls and structs they expand to.*
 ///
 /// # Arguments
 ///
+/// - **`by_ref`** – passes characters to tests by reference.
+/// - **`automaton`** – compiles the pattern into a deterministic automaton,
+///   which matches like [regex](https://docs.rs/regex) does (e.g. repetitions
+///   give characters back). Every test has to be a [simple test](crate#simple-tests)
+///   of `char` characters, and there could be no attributes other than `repeat`
+///   with literal bounds.
 /// - **`with_capture`** – sets the name of [`ExprMut::Capture`] struct.
+/// - **`with_capture_sink`** – uses an existing type (e.g. `with_capture_sink = "Spans"`)
+///   as [`ExprMut::Capture`] instead of a generated struct. The type has to
+///   implement [`CaptureSink`]. `count`, `capture_struct`, `variants`, `keep`
+///   and `required` cannot be used with such type.
 /// - **`with_capture_vis`** – sets visibility of [`ExprMut::Capture`] struct.
+/// - **`with_capture_derive`** – adds derives to [`ExprMut::Capture`] struct
+///   (e.g. `with_capture_derive = "Hash"`). The derives have to be valid for
+///   fields of `Option<Range<Idx>>` type, so e.g. `Copy` cannot be derived
+///   unless `capture_pairs` is passed.
+/// - **`capture_pairs`** – stores captured ranges as `(start, end)` pairs
+///   (e.g. `Option<(Idx, Idx)>`), which are [`Copy`] if `Idx` is.
+/// - **`with_capture_attr`** – adds attributes to [`ExprMut::Capture`] struct
+///   (e.g. `with_capture_attr = "must_use, non_exhaustive"`).
 ///
 /// [examples]: https://docs.rs/hitori-examples
 /// [`ExprMut::Capture`]: ExprMut::Capture
@@ -65,11 +110,43 @@ pub use hitori_macros::impl_expr;
 
 /// Implements [`ExprMut`] for the type.
 ///
+/// Alternatively, implements [`TryExprMut`] if it is the annotated trait impl.
+/// Tests of such expression return [`Result<bool, E>`](Result), where `E` is
+/// the type set by `type Error` item of the implementation.
+///
+/// [`ExprLen`] and [`ast::ExprAst`] are implemented as well. The implementation
+/// and capture struct are documented with the pattern, its captures
+/// and position assertions.
+///
 /// *See [examples] for code samples along with impls and structs they expand to.*
 ///
-/// *See [`impl_expr`] for arguments description.*
+/// # Arguments
+///
+/// - **`transactional`** – restores the state of the implementor changed in a
+///   branch that fails. The implementor has to be [`Clone`]. Passing
+///   `transactional = "checkpoint"` uses [`Checkpoint`] implementation instead.
+///
+/// *See [`impl_expr`] for the rest of arguments description.*
+///
+/// # Actions
+///
+/// Without `transactional`, `#[hitori::on_match(...)]` actions are queued
+/// and run only once the whole expression has matched, which requires `alloc`
+/// feature. With `transactional`, they run as soon as their subexpression
+/// matches and are rolled back with the rest of the state.
+///
+/// # Reset
+///
+/// Besides the `PATTERN` constant, the implementation may contain
+/// [`ExprMut::reset`] (or [`TryExprMut::reset`]) method.
 ///
 /// [examples]: https://docs.rs/hitori-examples
 #[cfg(feature = "macros")]
 #[cfg_attr(doc, doc(cfg(feature = "macros")))]
 pub use hitori_macros::impl_expr_mut;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec::Vec;
+}
@@ -1,8 +1,8 @@
-//! Items specific to [`ExprMut<usize, char>`]
+//! Items specific to [`ExprMut<usize, char>`] and [`TryExprMut<usize, char>`]
 
 use crate::{
     generic,
-    traits::{ExprMut, Match},
+    traits::{ExprMut, Match, TryExprMut},
 };
 #[cfg(feature = "alloc")]
 use alloc::{borrow::Cow, string::String};
@@ -15,6 +15,7 @@ pub struct CharEnds<'a> {
     next: char,
     indices: CharIndices<'a>,
     len: usize,
+    offset: usize,
 }
 
 impl<'a> CharEnds<'a> {
@@ -25,7 +26,20 @@ impl<'a> CharEnds<'a> {
             Some((_, next)) => (next, s.len()),
             None => (char::default(), 0),
         };
-        Self { next, indices, len }
+        Self {
+            next,
+            indices,
+            len,
+            offset: 0,
+        }
+    }
+
+    /// Like [`CharEnds::new`], but starts at `start` index of `s`
+    fn new_at(s: &'a str, start: usize) -> Self {
+        Self {
+            offset: start,
+            ..Self::new(&s[start..])
+        }
     }
 }
 
@@ -44,9 +58,19 @@ impl<'a> Iterator for CharEnds<'a> {
         if self.len == 0 {
             None
         } else if let Some((end, next)) = self.indices.next() {
-            Some((end, mem::replace(&mut self.next, next)))
+            Some((self.offset + end, mem::replace(&mut self.next, next)))
         } else {
-            Some((mem::replace(&mut self.len, 0), self.next))
+            Some((self.offset + mem::replace(&mut self.len, 0), self.next))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.len == 0 {
+            (0, Some(0))
+        } else {
+            let (lo, hi) = self.indices.size_hint();
+            (lo + 1, hi.map(|hi| hi + 1))
         }
     }
 }
@@ -113,13 +137,148 @@ pub fn repeat<E>(expr: E, s: &str) -> Repeat<E> {
     Repeat::new(expr, s)
 }
 
+/// Tries start positions from `start` to `lit_start` of the literal
+/// found in `s` with `matches`, returning what it found
+/// or the index after the literal start
+///
+/// Like [`generic::find_by`], passes the same `is_first` at every position.
+fn find_before_literal<'a, E, T, F>(
+    expr: &mut E,
+    s: &'a str,
+    start: usize,
+    is_first: bool,
+    lit_start: usize,
+    matches: &mut F,
+) -> Result<T, usize>
+where
+    E: ExprMut<usize, char>,
+    F: FnMut(&mut E, usize, bool, CharEnds<'a>) -> Option<T>,
+{
+    let mut start = start;
+    let mut iter = CharEnds::new_at(s, start);
+    loop {
+        let mut rest = iter.clone();
+        let next = rest.next();
+        if next
+            .as_ref()
+            .map_or(true, |(_, ch)| expr.can_start_with(ch))
+        {
+            expr.reset();
+            if let Some(found) = matches(expr, start, is_first, iter.clone()) {
+                return Ok(found);
+            }
+        }
+        match next {
+            Some((end, _)) if start < lit_start => {
+                start = end;
+                iter = rest;
+            }
+            Some((end, _)) => return Err(end),
+            None => return Err(start),
+        }
+    }
+}
+
+/// Like [`generic::find_by`], but searches for
+/// [`ExprMut::literal`] if any
+fn find_at_by<'a, E, T, F>(expr: &mut E, s: &'a str, start: usize, mut matches: F) -> Option<T>
+where
+    E: ExprMut<usize, char>,
+    F: FnMut(&mut E, usize, bool, CharEnds<'a>) -> Option<T>,
+{
+    let is_first = start == 0;
+    let literal = match expr.literal() {
+        Some(literal) if !literal.text.is_empty() => literal,
+        _ => return generic::find_by(expr, start, is_first, CharEnds::new_at(s, start), matches),
+    };
+    let mut start = start;
+    loop {
+        let lit_start = start + s[start..].find(literal.text)?;
+        let window_start = match literal.max_offset {
+            Some(max_offset) => s[start..lit_start]
+                .char_indices()
+                .rev()
+                .take(max_offset)
+                .last()
+                .map_or(lit_start, |(index, _)| start + index),
+            None => start,
+        };
+        match find_before_literal(expr, s, window_start, is_first, lit_start, &mut matches) {
+            Ok(found) => return Some(found),
+            Err(next_start) => start = next_start,
+        }
+    }
+}
+
+/// Like [`generic::find`], but searches for [`ExprMut::literal`] if any
+#[inline]
+fn find_at<E>(mut expr: E, s: &str, start: usize) -> Option<Match<usize, E::Capture, CharEnds>>
+where
+    E: ExprMut<usize, char>,
+{
+    find_at_by(&mut expr, s, start, E::starts_with_mut)
+}
+
 /// Finds the first substring that is matched by an [`ExprMut`]
+///
+/// If the expression has an [`ExprMut::literal`], it is searched for with
+/// [`str::find`], and only the start positions close enough to it are tried.
 #[inline]
 pub fn find<E>(expr: E, s: &str) -> Option<Match<usize, E::Capture, CharEnds>>
 where
     E: ExprMut<usize, char>,
 {
-    generic::find(expr, 0, true, CharEnds::from(s))
+    find_at(expr, s, 0)
+}
+
+/// Checks if a [`str`] starts with [`ExprMut`]-matched characters
+/// without capturing
+///
+/// *See [`generic::is_match`]*
+#[inline]
+pub fn is_match<E>(expr: E, s: &str) -> bool
+where
+    E: ExprMut<usize, char>,
+{
+    generic::is_match(expr, 0, true, CharEnds::from(s))
+}
+
+/// Checks if there is a substring that is matched by an [`ExprMut`]
+/// without capturing
+///
+/// Searches like [`find`] does.
+#[inline]
+pub fn contains<E>(mut expr: E, s: &str) -> bool
+where
+    E: ExprMut<usize, char>,
+{
+    find_at_by(&mut expr, s, 0, |expr, start, is_first, iter| {
+        expr.is_match_mut(start, is_first, iter).then_some(())
+    })
+    .is_some()
+}
+
+/// Checks if a [`str`] starts with [`TryExprMut`]-matched characters
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn try_starts_with<E>(
+    expr: E,
+    s: &str,
+) -> Result<Option<Match<usize, E::Capture, CharEnds>>, E::Error>
+where
+    E: TryExprMut<usize, char>,
+{
+    generic::try_starts_with(expr, 0, true, CharEnds::from(s))
+}
+
+/// Finds the first substring that is matched by a [`TryExprMut`]
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn try_find<E>(expr: E, s: &str) -> Result<Option<Match<usize, E::Capture, CharEnds>>, E::Error>
+where
+    E: TryExprMut<usize, char>,
+{
+    generic::try_find(expr, 0, true, CharEnds::from(s))
 }
 
 /// Iterator of successive non-overlapping [`find`]s
@@ -127,16 +286,12 @@ where
 pub struct FindIter<'a, E> {
     expr: E,
     start: usize,
-    iter: CharEnds<'a>,
+    s: &'a str,
 }
 
 impl<'a, E> FindIter<'a, E> {
     pub fn new(expr: E, s: &'a str) -> Self {
-        Self {
-            expr,
-            start: 0,
-            iter: s.into(),
-        }
+        Self { expr, start: 0, s }
     }
 }
 
@@ -147,14 +302,8 @@ where
     type Item = Match<usize, E::Capture, CharEnds<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let found = generic::find(
-            &mut self.expr,
-            self.start,
-            self.start == 0,
-            self.iter.clone(),
-        )?;
+        let found = find_at(&mut self.expr, self.s, self.start)?;
         self.start = found.range.end;
-        self.iter = found.iter_remainder.clone();
         Some(found)
     }
 }
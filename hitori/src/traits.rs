@@ -1,4 +1,4 @@
-use core::ops::Range;
+use core::{marker::PhantomData, ops::Range};
 
 /// Single [`ExprMut`] match
 #[derive(Clone)]
@@ -13,6 +13,18 @@ pub struct Match<Idx, C, I> {
     pub is_iter_advanced: bool,
 }
 
+/// Characters every [`ExprMut`] match contains in a row
+///
+/// *See [`ExprMut::literal`]*
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Literal {
+    /// The characters
+    pub text: &'static str,
+    /// Maximum number of characters preceding the literal in a match,
+    /// if it is bounded (`Some(0)` if every match starts with it)
+    pub max_offset: Option<usize>,
+}
+
 /// Expression with a mutable state
 pub trait ExprMut<Idx, Ch> {
     type Capture;
@@ -27,6 +39,94 @@ pub trait ExprMut<Idx, Ch> {
     where
         I: IntoIterator<Item = (Idx, Ch)>,
         I::IntoIter: Clone;
+
+    /// *See [`is_match`](crate::generic::is_match)*
+    ///
+    /// Calls [`starts_with_mut`](ExprMut::starts_with_mut) by default.
+    #[inline]
+    fn is_match_mut<I>(&mut self, start: Idx, is_first: bool, iter: I) -> bool
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone,
+    {
+        self.starts_with_mut(start, is_first, iter).is_some()
+    }
+
+    /// Brings the state back to where it was before any matching.
+    ///
+    /// Called by [`starts_with`](crate::generic::starts_with),
+    /// [`find`](crate::generic::find) and their [`string`](crate::string)
+    /// counterparts before each attempt to match. Does nothing by default.
+    #[inline]
+    fn reset(&mut self) {}
+
+    /// Minimum number of characters consumed by a match.
+    ///
+    /// Used by [`find`](crate::generic::find) to stop at start positions
+    /// that leave too little input. Returns `0` by default.
+    #[inline]
+    fn min_len(&self) -> usize {
+        0
+    }
+
+    /// Whether a match could start with the character.
+    ///
+    /// Used by [`find`](crate::generic::find) to skip start positions
+    /// without trying to match. Returns `false` only if no match starts
+    /// with the character, and `true` by default.
+    #[inline]
+    fn can_start_with(&self, _ch: &Ch) -> bool {
+        true
+    }
+
+    /// Characters every match contains in a row.
+    ///
+    /// Used by [`string::find`](crate::string::find) to search for the literal
+    /// and only try start positions close enough to it. Returns `None` by default.
+    #[inline]
+    fn literal(&self) -> Option<Literal> {
+        None
+    }
+}
+
+/// Expression with a mutable state whose tests could fail with an error
+pub trait TryExprMut<Idx, Ch> {
+    type Capture;
+    type Error;
+
+    /// *See [`try_starts_with`](crate::generic::try_starts_with)*
+    #[allow(clippy::type_complexity)]
+    fn try_starts_with_mut<I>(
+        &mut self,
+        start: Idx,
+        is_first: bool,
+        iter: I,
+    ) -> Result<Option<Match<Idx, Self::Capture, I::IntoIter>>, Self::Error>
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone;
+
+    /// *See [`ExprMut::reset`]*
+    #[inline]
+    fn reset(&mut self) {}
+
+    /// *See [`ExprMut::min_len`]*
+    #[inline]
+    fn min_len(&self) -> usize {
+        0
+    }
+
+    /// *See [`ExprMut::can_start_with`]*
+    #[inline]
+    fn can_start_with(&self, _ch: &Ch) -> bool {
+        true
+    }
+
+    /// *See [`ExprMut::literal`]*
+    #[inline]
+    fn literal(&self) -> Option<Literal> {
+        None
+    }
 }
 
 /// Expression with an immutable state
@@ -41,6 +141,228 @@ pub trait Expr<Idx, Ch>: ExprMut<Idx, Ch> {
     where
         I: IntoIterator<Item = (Idx, Ch)>,
         I::IntoIter: Clone;
+
+    /// *See [`is_match`](crate::generic::is_match)*
+    ///
+    /// Calls [`starts_with`](Expr::starts_with) by default.
+    #[inline]
+    fn is_match<I>(&self, start: Idx, is_first: bool, iter: I) -> bool
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone,
+    {
+        self.starts_with(start, is_first, iter).is_some()
+    }
+}
+
+/// Expression with an immutable state that reads an external context
+///
+/// *See [`With`] for using it as an [`Expr`]*
+pub trait ExprWith<Idx, Ch, Ctx> {
+    type Capture;
+
+    /// *See [`starts_with`](crate::generic::starts_with)*
+    ///
+    /// `ctx` is passed to the expression along with the other arguments.
+    fn starts_with_ctx<I>(
+        &self,
+        ctx: Ctx,
+        start: Idx,
+        is_first: bool,
+        iter: I,
+    ) -> Option<Match<Idx, Self::Capture, I::IntoIter>>
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone;
+
+    /// *See [`ExprMut::min_len`]*
+    #[inline]
+    fn min_len(&self) -> usize {
+        0
+    }
+
+    /// *See [`ExprMut::can_start_with`]*
+    #[inline]
+    fn can_start_with(&self, _ch: &Ch) -> bool {
+        true
+    }
+
+    /// *See [`ExprMut::literal`]*
+    #[inline]
+    fn literal(&self) -> Option<Literal> {
+        None
+    }
+}
+
+/// [`ExprWith`] paired with its context, which makes it an [`Expr`]
+#[derive(Clone)]
+pub struct With<E, Ctx> {
+    pub expr: E,
+    pub ctx: Ctx,
+}
+
+impl<E, Ctx> With<E, Ctx> {
+    pub fn new(expr: E, ctx: Ctx) -> Self {
+        Self { expr, ctx }
+    }
+}
+
+impl<Idx, Ch, Ctx, E> ExprMut<Idx, Ch> for With<E, Ctx>
+where
+    Ctx: Clone,
+    E: ExprWith<Idx, Ch, Ctx>,
+{
+    type Capture = E::Capture;
+
+    #[inline]
+    fn starts_with_mut<I>(
+        &mut self,
+        start: Idx,
+        is_first: bool,
+        iter: I,
+    ) -> Option<Match<Idx, Self::Capture, I::IntoIter>>
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone,
+    {
+        <Self as Expr<Idx, Ch>>::starts_with(self, start, is_first, iter)
+    }
+
+    #[inline]
+    fn min_len(&self) -> usize {
+        self.expr.min_len()
+    }
+
+    #[inline]
+    fn can_start_with(&self, ch: &Ch) -> bool {
+        self.expr.can_start_with(ch)
+    }
+
+    #[inline]
+    fn literal(&self) -> Option<Literal> {
+        self.expr.literal()
+    }
+}
+
+impl<Idx, Ch, Ctx, E> Expr<Idx, Ch> for With<E, Ctx>
+where
+    Ctx: Clone,
+    E: ExprWith<Idx, Ch, Ctx>,
+{
+    #[inline]
+    fn starts_with<I>(
+        &self,
+        start: Idx,
+        is_first: bool,
+        iter: I,
+    ) -> Option<Match<Idx, Self::Capture, I::IntoIter>>
+    where
+        I: IntoIterator<Item = (Idx, Ch)>,
+        I::IntoIter: Clone,
+    {
+        self.expr
+            .starts_with_ctx(self.ctx.clone(), start, is_first, iter)
+    }
+}
+
+/// State of an [`ExprMut`] that is saved before a branch and rolled back
+/// if the branch fails.
+///
+/// *See `transactional` argument of [`impl_expr_mut`](crate::impl_expr_mut)*
+pub trait Checkpoint {
+    type Saved;
+
+    /// Saves the current state
+    fn checkpoint(&self) -> Self::Saved;
+
+    /// Restores the state saved by [`checkpoint`](Checkpoint::checkpoint)
+    fn rollback(&mut self, saved: Self::Saved);
+}
+
+/// Bounds of the number of characters consumed by a match
+///
+/// *Implemented by [`impl_expr`](crate::impl_expr) and
+/// [`impl_expr_mut`](crate::impl_expr_mut) using literal repetition bounds*
+pub trait ExprLen<Idx, Ch> {
+    /// Minimum number of characters consumed by a match
+    const MIN_LEN: usize;
+
+    /// Maximum number of characters consumed by a match,
+    /// if it is known to be bounded
+    const MAX_LEN: Option<usize>;
+}
+
+/// Destination of captured ranges provided instead of a generated
+/// capture struct
+///
+/// *See `with_capture_sink` argument of [`impl_expr`](crate::impl_expr)*
+pub trait CaptureSink<Idx>: Default {
+    type Snapshot;
+
+    /// Records `range` captured into destination `name`
+    fn set(&mut self, name: &'static str, range: Range<Idx>);
+
+    /// Saves captured ranges before a branch that could fail
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Restores ranges saved by [`snapshot`](CaptureSink::snapshot)
+    /// after the branch has failed
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+/// Capture struct generated by [`impl_expr`](crate::impl_expr) or
+/// [`impl_expr_mut`](crate::impl_expr_mut), accessed by capture names.
+///
+/// Fields of nested structs are named with dots (e.g. `domain.name`), fields
+/// of `variants` structs are prefixed with the field and variant name
+/// (e.g. `variant.Square.side`). Counts are not included.
+pub trait Captures<Idx> {
+    /// Names of every capture
+    const NAMES: &'static [&'static str];
+
+    /// Returns the range of the capture named `name`, if it is known and
+    /// was captured
+    fn get(&self, name: &str) -> Option<Range<Idx>>;
+
+    /// Iterates over `(name, range)` pairs of captured ranges
+    #[inline]
+    fn iter(&self) -> CapturesIter<'_, Idx, Self> {
+        CapturesIter {
+            captures: self,
+            names: Self::NAMES.iter(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Iterator returned by [`Captures::iter`]
+pub struct CapturesIter<'a, Idx, C: ?Sized> {
+    captures: &'a C,
+    names: core::slice::Iter<'static, &'static str>,
+    phantom: PhantomData<Idx>,
+}
+
+impl<'a, Idx, C: Captures<Idx> + ?Sized> Iterator for CapturesIter<'a, Idx, C> {
+    type Item = (&'static str, Range<Idx>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for name in &mut self.names {
+            if let Some(range) = self.captures.get(name) {
+                return Some((name, range));
+            }
+        }
+        None
+    }
+}
+
+/// Capture struct which index type could be converted
+///
+/// *See [`Captures`]*
+pub trait MapIdx<Idx, NewIdx> {
+    type Output;
+
+    /// Converts every index of captured ranges with `f`
+    fn map_idx<F: FnMut(Idx) -> NewIdx>(self, f: F) -> Self::Output;
 }
 
 macro_rules! impl_mut_for_mut {
@@ -61,6 +383,35 @@ macro_rules! impl_mut_for_mut {
             {
                 E::starts_with_mut(self, start, is_first, iter)
             }
+
+            #[inline]
+            fn is_match_mut<I>(&mut self, start: Idx, is_first: bool, iter: I) -> bool
+            where
+                I: IntoIterator<Item = (Idx, Ch)>,
+                I::IntoIter: Clone,
+            {
+                E::is_match_mut(self, start, is_first, iter)
+            }
+
+            #[inline]
+            fn reset(&mut self) {
+                E::reset(self);
+            }
+
+            #[inline]
+            fn min_len(&self) -> usize {
+                E::min_len(self)
+            }
+
+            #[inline]
+            fn can_start_with(&self, ch: &Ch) -> bool {
+                E::can_start_with(self, ch)
+            }
+
+            #[inline]
+            fn literal(&self) -> Option<Literal> {
+                E::literal(self)
+            }
         }
     };
 }
@@ -71,16 +422,85 @@ impl_mut_for_mut!(&mut E);
 #[cfg_attr(doc, doc(cfg(feature = "alloc")))]
 impl_mut_for_mut!(alloc::boxed::Box<E>);
 
+macro_rules! impl_try_mut_for_mut {
+    ($ty:ty) => {
+        impl<'a, Idx, Ch, E: TryExprMut<Idx, Ch>> TryExprMut<Idx, Ch> for $ty {
+            type Capture = E::Capture;
+            type Error = E::Error;
+
+            #[inline]
+            fn try_starts_with_mut<I>(
+                &mut self,
+                start: Idx,
+                is_first: bool,
+                iter: I,
+            ) -> Result<Option<Match<Idx, Self::Capture, I::IntoIter>>, Self::Error>
+            where
+                I: IntoIterator<Item = (Idx, Ch)>,
+                I::IntoIter: Clone,
+            {
+                E::try_starts_with_mut(self, start, is_first, iter)
+            }
+
+            #[inline]
+            fn reset(&mut self) {
+                E::reset(self);
+            }
+
+            #[inline]
+            fn min_len(&self) -> usize {
+                E::min_len(self)
+            }
+
+            #[inline]
+            fn can_start_with(&self, ch: &Ch) -> bool {
+                E::can_start_with(self, ch)
+            }
+
+            #[inline]
+            fn literal(&self) -> Option<Literal> {
+                E::literal(self)
+            }
+        }
+    };
+}
+
+impl_try_mut_for_mut!(&mut E);
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc, doc(cfg(feature = "alloc")))]
+impl_try_mut_for_mut!(alloc::boxed::Box<E>);
+
 macro_rules! impl_for_const {
     ($ty:ty: ExprMut) => {
-        impl_for_const!($ty: ExprMut::starts_with_mut(mut, Capture));
+        impl_for_const!($ty: ExprMut::{starts_with_mut, is_match_mut}(mut, Capture));
     };
     ($ty:ty: Expr) => {
-        impl_for_const!($ty: Expr::starts_with);
+        impl_for_const!($ty: Expr::{starts_with, is_match});
     };
-    ($ty:ty: $trait:ident::$starts_with:ident$(($mut:ident, $capture:ident))?) => {
+    (
+        $ty:ty: $trait:ident::{$starts_with:ident, $is_match:ident}
+        $(($mut:ident, $capture:ident))?
+    ) => {
         impl<'a, Idx, Ch, E: Expr<Idx, Ch>> $trait<Idx, Ch> for $ty {
-            $(type $capture = E::Capture;)?
+            $(
+                type $capture = E::Capture;
+
+                #[inline]
+                fn min_len(&self) -> usize {
+                    <E as ExprMut<Idx, Ch>>::min_len(self)
+                }
+
+                #[inline]
+                fn can_start_with(&self, ch: &Ch) -> bool {
+                    <E as ExprMut<Idx, Ch>>::can_start_with(self, ch)
+                }
+
+                #[inline]
+                fn literal(&self) -> Option<Literal> {
+                    <E as ExprMut<Idx, Ch>>::literal(self)
+                }
+            )?
 
             #[inline]
             fn $starts_with<I>(
@@ -95,6 +515,15 @@ macro_rules! impl_for_const {
             {
                 E::starts_with(self, start, is_first, iter)
             }
+
+            #[inline]
+            fn $is_match<I>(&$($mut)?self, start: Idx, is_first: bool, iter: I) -> bool
+            where
+                I: IntoIterator<Item = (Idx, Ch)>,
+                I::IntoIter: Clone,
+            {
+                E::is_match(self, start, is_first, iter)
+            }
         }
     };
 }
@@ -106,3 +535,66 @@ impl_for_const!(&E: Expr);
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc, doc(cfg(feature = "alloc")))]
 impl_for_const!(alloc::boxed::Box<E>: Expr);
+
+macro_rules! impl_with_for_const {
+    ($ty:ty) => {
+        impl<'a, Idx, Ch, Ctx, E: ExprWith<Idx, Ch, Ctx>> ExprWith<Idx, Ch, Ctx> for $ty {
+            type Capture = E::Capture;
+
+            #[inline]
+            fn starts_with_ctx<I>(
+                &self,
+                ctx: Ctx,
+                start: Idx,
+                is_first: bool,
+                iter: I,
+            ) -> Option<Match<Idx, Self::Capture, I::IntoIter>>
+            where
+                I: IntoIterator<Item = (Idx, Ch)>,
+                I::IntoIter: Clone,
+            {
+                E::starts_with_ctx(self, ctx, start, is_first, iter)
+            }
+
+            #[inline]
+            fn min_len(&self) -> usize {
+                E::min_len(self)
+            }
+
+            #[inline]
+            fn can_start_with(&self, ch: &Ch) -> bool {
+                E::can_start_with(self, ch)
+            }
+
+            #[inline]
+            fn literal(&self) -> Option<Literal> {
+                E::literal(self)
+            }
+        }
+    };
+}
+
+impl_with_for_const!(&E);
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc, doc(cfg(feature = "alloc")))]
+impl_with_for_const!(alloc::boxed::Box<E>);
+
+macro_rules! impl_len_for_wrapper {
+    ($ty:ty$(, $param:ident)?) => {
+        impl<'a, Idx, Ch, E: ExprLen<Idx, Ch>$(, $param)?> ExprLen<Idx, Ch> for $ty {
+            const MIN_LEN: usize = E::MIN_LEN;
+            const MAX_LEN: Option<usize> = E::MAX_LEN;
+        }
+    };
+}
+
+impl_len_for_wrapper!(&'a E);
+
+impl_len_for_wrapper!(&'a mut E);
+
+impl_len_for_wrapper!(With<E, Ctx>, Ctx);
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc, doc(cfg(feature = "alloc")))]
+impl_len_for_wrapper!(alloc::boxed::Box<E>);